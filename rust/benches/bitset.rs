@@ -0,0 +1,138 @@
+//! Flood fills large random mazes while tracking visited tiles, and intersects many random sets
+//! of node ids, once with the bit-packed sets and once with `HashSet`.
+use std::collections::{HashSet, VecDeque};
+
+use advent_of_code::{
+    bitset::{BitGrid, DenseBitSet},
+    geometry::{Direction, Index},
+};
+use common::{xorshift, SEED};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+mod common;
+
+/// A square maze where about a quarter of the tiles are walls, with an open top left corner
+/// that leads into the large connected part of the maze.
+fn maze(size: usize) -> Vec<Vec<bool>> {
+    let mut random = xorshift(SEED);
+    let mut maze: Vec<Vec<bool>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| random.next().unwrap().is_multiple_of(4))
+                .collect()
+        })
+        .collect();
+    maze[0][0] = false;
+    maze[0][1] = false;
+    maze[1][0] = false;
+    maze
+}
+
+/// Counts the open tiles reachable from the top left corner with a breadth-first search.
+fn flood_fill(maze: &[Vec<bool>], mut visit: impl FnMut(Index) -> bool) -> usize {
+    let shape = (maze.len(), maze.len());
+    let mut queue = VecDeque::from([(0, 0)]);
+    visit((0, 0));
+
+    let mut reached = 0;
+    while let Some(position) = queue.pop_front() {
+        reached += 1;
+        for direction in Direction::ALL {
+            match direction.step_index(position, shape) {
+                Some(next) if !maze[next.0][next.1] && visit(next) => queue.push_back(next),
+                _ => {}
+            }
+        }
+    }
+    reached
+}
+
+fn flood_fill_with_bit_grid(maze: &[Vec<bool>]) -> usize {
+    let mut visited = BitGrid::new(maze.len(), maze.len());
+    flood_fill(maze, |position| visited.insert(position))
+}
+
+fn flood_fill_with_hash_set(maze: &[Vec<bool>]) -> usize {
+    let mut visited = HashSet::new();
+    flood_fill(maze, |position| visited.insert(position))
+}
+
+fn visited(c: &mut Criterion) {
+    let mut group = c.benchmark_group("visited");
+    group.sample_size(10);
+
+    for size in [141, 500, 1_000] {
+        let maze = maze(size);
+        assert_eq!(
+            flood_fill_with_bit_grid(&maze),
+            flood_fill_with_hash_set(&maze)
+        );
+
+        group.bench_with_input(BenchmarkId::new("bit grid", size), &maze, |b, maze| {
+            b.iter(|| flood_fill_with_bit_grid(black_box(maze)))
+        });
+        group.bench_with_input(BenchmarkId::new("hash set", size), &maze, |b, maze| {
+            b.iter(|| flood_fill_with_hash_set(black_box(maze)))
+        });
+    }
+    group.finish();
+}
+
+/// `count` random sets of ids below `universe`, each holding about an eighth of them.
+fn random_sets(count: usize, universe: usize) -> Vec<Vec<usize>> {
+    let mut random = xorshift(0x9e37_79b9_7f4a_7c15);
+    (0..count)
+        .map(|_| {
+            (0..universe)
+                .filter(|_| random.next().unwrap().is_multiple_of(8))
+                .collect()
+        })
+        .collect()
+}
+
+/// The sum of the intersection sizes of all pairs of sets, as in picking a clique pivot.
+fn pairwise_intersections<S>(sets: &[S], intersection_len: impl Fn(&S, &S) -> usize) -> usize {
+    sets.iter()
+        .flat_map(|a| sets.iter().map(move |b| (a, b)))
+        .map(|(a, b)| intersection_len(a, b))
+        .sum()
+}
+
+fn intersection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intersection");
+    group.sample_size(10);
+
+    for universe in [520, 2_000, 10_000] {
+        let sets = random_sets(100, universe);
+        let bit_sets: Vec<DenseBitSet> = sets
+            .iter()
+            .map(|set| set.iter().copied().collect())
+            .collect();
+        let hash_sets: Vec<HashSet<usize>> = sets
+            .iter()
+            .map(|set| set.iter().copied().collect())
+            .collect();
+
+        let with_bit_sets =
+            |sets: &[DenseBitSet]| pairwise_intersections(sets, |a, b| a.intersection_len(b));
+        let with_hash_sets = |sets: &[HashSet<usize>]| {
+            pairwise_intersections(sets, |a, b| a.intersection(b).count())
+        };
+        assert_eq!(with_bit_sets(&bit_sets), with_hash_sets(&hash_sets));
+
+        group.bench_with_input(
+            BenchmarkId::new("bit set", universe),
+            &bit_sets,
+            |b, sets| b.iter(|| with_bit_sets(black_box(sets))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("hash set", universe),
+            &hash_sets,
+            |b, sets| b.iter(|| with_hash_sets(black_box(sets))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, visited, intersection);
+criterion_main!(benches);
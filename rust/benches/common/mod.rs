@@ -0,0 +1,14 @@
+//! Inputs shared by the benchmarks.
+
+/// The seed of the pseudo-random inputs, so that every run measures the same data.
+pub const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// A fixed pseudo-random sequence from an xorshift generator, which must not start at `0`.
+pub fn xorshift(mut state: u64) -> impl Iterator<Item = u64> {
+    std::iter::from_fn(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        Some(state)
+    })
+}
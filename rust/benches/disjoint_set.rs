@@ -0,0 +1,111 @@
+//! Finds the first byte that cuts the top left corner of a day 18 style memory grid off from
+//! the bottom right one, once with a binary search that reruns a BFS at every probe and once by
+//! removing the bytes in reverse order and merging the freed tiles with the `first_separating`
+//! of day 18.
+use advent_of_code::{
+    day,
+    disjoint_set::first_separating,
+    geometry::{Direction, Index},
+    search::{bfs, Cost, Problem},
+    template::read_file,
+};
+use common::{xorshift, SEED};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+mod common;
+
+/// Every tile of a `size` x `size` grid but the corners, in a fixed pseudo-random order.
+fn falling_bytes(size: usize) -> Vec<Index> {
+    let mut bytes: Vec<Index> = (0..size * size)
+        .map(|i| (i / size, i % size))
+        .filter(|&position| position != (0, 0) && position != (size - 1, size - 1))
+        .collect();
+
+    let mut random = xorshift(SEED);
+    for i in (1..bytes.len()).rev() {
+        bytes.swap(i, random.next().unwrap() as usize % (i + 1));
+    }
+    bytes
+}
+
+struct Memory {
+    size: usize,
+    blocked: Vec<bool>,
+}
+
+impl Problem for Memory {
+    type State = Index;
+
+    fn successors(&self, &position: &Self::State) -> Vec<(Self::State, Cost)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| direction.step_index(position, (self.size, self.size)))
+            .filter(|&(row, column)| !self.blocked[row * self.size + column])
+            .map(|next| (next, 1))
+            .collect()
+    }
+
+    fn is_goal(&self, &state: &Self::State) -> bool {
+        state == (self.size - 1, self.size - 1)
+    }
+}
+
+fn with_binary_search(size: usize, bytes: &[Index]) -> usize {
+    let is_open = |count: usize| {
+        let mut blocked = vec![false; size * size];
+        for &(row, column) in &bytes[..count] {
+            blocked[row * size + column] = true;
+        }
+        bfs(&Memory { size, blocked }, [(0, 0)]).cost().is_some()
+    };
+    (0..=bytes.len())
+        .collect::<Vec<_>>()
+        .partition_point(|&count| is_open(count))
+        - 1
+}
+
+fn with_disjoint_set(size: usize, bytes: &[Index]) -> Option<usize> {
+    first_separating((size, size), bytes, (0, 0), (size - 1, size - 1))
+}
+
+/// The falling bytes of the day 18 example, as positions `(y, x)` on a 7 x 7 grid.
+fn example_bytes() -> Vec<Index> {
+    read_file("examples", day!(18))
+        .lines()
+        .filter_map(|line| line.trim().split_once(','))
+        .map(|(x, y)| (y.parse().unwrap(), x.parse().unwrap()))
+        .collect()
+}
+
+fn blocking_byte(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blocking byte");
+    group.sample_size(10);
+
+    // the binary search must still agree with the answer of day 18, the byte at `6,1`.
+    let bytes = example_bytes();
+    assert_eq!(with_binary_search(7, &bytes), 20);
+    assert_eq!(with_disjoint_set(7, &bytes), Some(20));
+
+    for size in [71, 213] {
+        let bytes = falling_bytes(size);
+        assert_eq!(
+            Some(with_binary_search(size, &bytes)),
+            with_disjoint_set(size, &bytes)
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("binary search", size),
+            &bytes,
+            |b, bytes| b.iter(|| with_binary_search(size, black_box(bytes))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("disjoint set", size),
+            &bytes,
+            |b, bytes| b.iter(|| with_disjoint_set(size, black_box(bytes))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, blocking_byte);
+criterion_main!(benches);
@@ -0,0 +1,106 @@
+//! Compacts large day 9 style disk maps by moving whole files into the leftmost free span,
+//! once with [`Allocator`] and once with a linear scan over a list of segments.
+use advent_of_code::interval::{checksum, Allocator, Extent};
+use common::{xorshift, SEED};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+mod common;
+
+/// A disk map of `len` digits from a fixed pseudo-random sequence.
+fn disk_map(len: usize) -> Vec<u64> {
+    xorshift(SEED)
+        .take(len)
+        .enumerate()
+        .map(|(i, state)| {
+            let digit = state % 10;
+            if i % 2 == 0 {
+                digit.max(1)
+            } else {
+                digit
+            }
+        })
+        .collect()
+}
+
+fn compact_with_allocator(map: &[u64]) -> u64 {
+    let mut files = Vec::new();
+    let mut free = Allocator::new();
+    let mut position = 0;
+    for (i, &len) in map.iter().enumerate() {
+        let extent = Extent::new(position, len);
+        if i % 2 == 0 {
+            files.push(((i / 2) as u64, extent));
+        } else {
+            free.release(extent);
+        }
+        position = extent.end();
+    }
+
+    let mut compacted = Vec::new();
+    for &(id, file) in files.iter().rev() {
+        match free.allocate_before(file.len, file.start) {
+            Some(extent) => {
+                free.release(file);
+                compacted.push((id, extent));
+            }
+            None => compacted.push((id, file)),
+        }
+    }
+    checksum(compacted)
+}
+
+fn compact_with_linear_scan(map: &[u64]) -> u64 {
+    // (file id, length) in disk order.
+    let mut segments: Vec<(Option<u64>, u64)> = map
+        .iter()
+        .enumerate()
+        .map(|(i, &len)| ((i % 2 == 0).then_some(i as u64 / 2), len))
+        .collect();
+
+    for id in (0..map.len().div_ceil(2) as u64).rev() {
+        let index = segments.iter().position(|s| s.0 == Some(id)).unwrap();
+        let len = segments[index].1;
+        let Some(free) = segments[..index]
+            .iter()
+            .position(|s| s.0.is_none() && s.1 >= len)
+        else {
+            continue;
+        };
+
+        segments[index].0 = None;
+        let remainder = segments[free].1 - len;
+        segments[free] = (Some(id), len);
+        if remainder > 0 {
+            segments.insert(free + 1, (None, remainder));
+        }
+    }
+
+    let mut position = 0;
+    let files = segments.into_iter().filter_map(|(id, len)| {
+        let extent = Extent::new(position, len);
+        position = extent.end();
+        Some((id?, extent))
+    });
+    checksum(files)
+}
+
+fn compaction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compaction");
+    group.sample_size(10);
+
+    for len in [2_000, 20_000, 50_000] {
+        let map = disk_map(len);
+        assert_eq!(compact_with_allocator(&map), compact_with_linear_scan(&map));
+
+        group.bench_with_input(BenchmarkId::new("allocator", len), &map, |b, map| {
+            b.iter(|| compact_with_allocator(black_box(map)))
+        });
+        group.bench_with_input(BenchmarkId::new("linear scan", len), &map, |b, map| {
+            b.iter(|| compact_with_linear_scan(black_box(map)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, compaction);
+criterion_main!(benches);
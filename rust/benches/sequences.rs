@@ -0,0 +1,128 @@
+//! Finds the best sequence of four price changes for day 22 style buyers, once with the
+//! original `HashMap` of tuples and once with a dense table indexed by a base-19 encoding.
+//! With the `parallel` feature, the dense tables are also filled on several threads.
+use std::collections::HashMap;
+
+use common::{xorshift, SEED};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::Itertools;
+
+mod common;
+
+const fn evolve(secret: u64) -> u64 {
+    let mut result = (secret ^ secret << 6) % 16777216;
+    result = (result ^ result >> 5) % 16777216;
+    (result ^ result << 11) % 16777216
+}
+
+/// The initial secrets of `count` buyers from a fixed pseudo-random sequence.
+fn secrets(count: usize) -> Vec<u64> {
+    xorshift(SEED)
+        .take(count)
+        .map(|state| state % 16777216)
+        .collect()
+}
+
+fn with_hash_map(secrets: &[u64]) -> Option<u32> {
+    let sequences = secrets.iter().flat_map(|&secret| {
+        (0..2000)
+            .scan(secret, |current, _| {
+                *current = evolve(*current);
+                Some(*current)
+            })
+            .map(|n| (n % 10) as i8)
+            .tuple_windows()
+            .map(|(a, b)| (b, b - a))
+            .tuple_windows::<(_, _, _, _)>()
+            .map(|(t1, t2, t3, t4)| ((t1.1, t2.1, t3.1, t4.1), t4.0))
+            .unique_by(|(sequence, _)| *sequence)
+    });
+
+    let mut bananas = HashMap::new();
+    for (sequence, price) in sequences {
+        *bananas.entry(sequence).or_insert(0) += price as u32;
+    }
+    bananas.into_values().max()
+}
+
+const SEQUENCES: usize = 19 * 19 * 19 * 19;
+
+fn bananas(secrets: &[u64]) -> Vec<u32> {
+    let mut bananas = vec![0; SEQUENCES];
+    let mut seen = vec![0; SEQUENCES];
+
+    for (buyer, &secret) in (1..).zip(secrets) {
+        let mut secret = secret;
+        let mut price = (secret % 10) as usize;
+        let mut sequence = 0;
+
+        for i in 0..2000 {
+            secret = evolve(secret);
+            let next_price = (secret % 10) as usize;
+            sequence = (sequence * 19 + next_price + 9 - price) % SEQUENCES;
+            price = next_price;
+
+            if i >= 3 && seen[sequence] != buyer {
+                seen[sequence] = buyer;
+                bananas[sequence] += price as u32;
+            }
+        }
+    }
+    bananas
+}
+
+fn with_dense_table(secrets: &[u64]) -> Option<u32> {
+    bananas(secrets).into_iter().max()
+}
+
+#[cfg(feature = "parallel")]
+fn with_parallel_dense_tables(secrets: &[u64]) -> Option<u32> {
+    use rayon::prelude::*;
+
+    let add = |mut total: Vec<u32>, bananas: Vec<u32>| {
+        total.iter_mut().zip(bananas).for_each(|(t, b)| *t += b);
+        total
+    };
+    secrets
+        .par_chunks(256)
+        .map(bananas)
+        .reduce(|| vec![0; SEQUENCES], add)
+        .into_iter()
+        .max()
+}
+
+fn best_sequence(c: &mut Criterion) {
+    let mut group = c.benchmark_group("best sequence");
+    group.sample_size(10);
+
+    // the copies of day 22 must still agree with the answer of its part two example.
+    let example = [1, 2, 3, 2024];
+    assert_eq!(with_hash_map(&example), Some(23));
+    assert_eq!(with_dense_table(&example), Some(23));
+    #[cfg(feature = "parallel")]
+    assert_eq!(with_parallel_dense_tables(&example), Some(23));
+
+    for count in [500, 2_000] {
+        let secrets = secrets(count);
+        assert_eq!(with_hash_map(&secrets), with_dense_table(&secrets));
+
+        group.bench_with_input(
+            BenchmarkId::new("hash map", count),
+            &secrets,
+            |b, secrets| b.iter(|| with_hash_map(black_box(secrets))),
+        );
+        group.bench_with_input(BenchmarkId::new("dense", count), &secrets, |b, secrets| {
+            b.iter(|| with_dense_table(black_box(secrets)))
+        });
+        #[cfg(feature = "parallel")]
+        group.bench_with_input(
+            BenchmarkId::new("dense parallel", count),
+            &secrets,
+            |b, secrets| b.iter(|| with_parallel_dense_tables(black_box(secrets))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, best_sequence);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+//! Searches the register A that makes a program output itself, once by backtracking over the
+//! octal digits of A and once by trying every A in turn, and runs a program of puzzle size.
+use advent_of_code::vm::{find_quine, Machine, Registers};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// The quine of the puzzle example.
+const EXAMPLE: [u8; 6] = [0, 3, 5, 4, 3, 0];
+
+/// A program shaped like the puzzle inputs: it outputs a hash of the lowest octal digits of A
+/// per iteration. It has no quine, so the search has to try every branch.
+const HASH_LOOP: [u8; 16] = [2, 4, 1, 3, 7, 5, 4, 1, 1, 5, 0, 3, 5, 5, 3, 0];
+
+fn brute_force(program: &[u8]) -> Option<u64> {
+    (0..).find(|&a| {
+        let mut machine = Machine::new(Registers { a, b: 0, c: 0 });
+        machine.run(program).is_ok_and(|output| output == program)
+    })
+}
+
+fn quine(c: &mut Criterion) {
+    let mut group = c.benchmark_group("quine");
+    group.sample_size(10);
+
+    assert_eq!(find_quine(&EXAMPLE), brute_force(&EXAMPLE));
+    group.bench_function("backtracking/example", |b| {
+        b.iter(|| find_quine(black_box(&EXAMPLE)))
+    });
+    group.bench_function("brute force/example", |b| {
+        b.iter(|| brute_force(black_box(&EXAMPLE)))
+    });
+    group.bench_function("backtracking/hash loop", |b| {
+        b.iter(|| find_quine(black_box(&HASH_LOOP)))
+    });
+    group.finish();
+}
+
+fn run(c: &mut Criterion) {
+    let registers = Registers {
+        a: 0o1234_5670_1234_5670,
+        b: 0,
+        c: 0,
+    };
+    c.bench_function("run/hash loop", |b| {
+        b.iter(|| {
+            let mut machine = Machine::new(black_box(registers));
+            machine.run(black_box(&HASH_LOOP)).unwrap().len()
+        })
+    });
+}
+
+criterion_group!(benches, quine, run);
+criterion_main!(benches);
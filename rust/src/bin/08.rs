@@ -1,11 +1,11 @@
+use advent_of_code::{bitset::BitGrid, geometry::Point};
 use itertools::Itertools;
-use num_complex::Complex;
 use std::collections::HashMap;
 
 advent_of_code::solution!(8);
 
-fn is_in_bounds(coord: &Complex<i32>, width: i32, height: i32) -> bool {
-    0 <= coord.re && coord.re < width && 0 <= coord.im && coord.im < height
+fn is_in_bounds(coord: &Point, width: i32, height: i32) -> bool {
+    0 <= coord.x && coord.x < width && 0 <= coord.y && coord.y < height
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -16,7 +16,7 @@ pub fn part_one(input: &str) -> Option<u32> {
             match c {
                 '.' => continue,
                 c => {
-                    let coord = Complex::new(x as i32, y as i32);
+                    let coord = Point::new(x as i32, y as i32);
                     positions_by_type.entry(c).or_insert(vec![]).push(coord);
                 }
             }
@@ -26,16 +26,17 @@ pub fn part_one(input: &str) -> Option<u32> {
     let width = input.lines().next()?.len() as i32;
     let height = input.lines().count() as i32;
 
-    positions_by_type
-        .values()
-        .flat_map(|coords| coords.iter().permutations(2))
-        .map(|coords| (coords[0], coords[1]))
-        .map(|(coord1, coord2)| (*coord1 - *coord2) + *coord1)
-        .filter(|antinode| is_in_bounds(antinode, width, height))
-        .unique()
-        .count()
-        .try_into()
-        .ok()
+    let mut antinodes = BitGrid::new(width as usize, height as usize);
+    antinodes.extend(
+        positions_by_type
+            .values()
+            .flat_map(|coords| coords.iter().permutations(2))
+            .map(|coords| (coords[0], coords[1]))
+            .map(|(coord1, coord2)| (*coord1 - *coord2) + *coord1)
+            .filter(|antinode| is_in_bounds(antinode, width, height)),
+    );
+
+    antinodes.len().try_into().ok()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -46,7 +47,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             match c {
                 '.' => continue,
                 c => {
-                    let coord = Complex::new(x as i32, y as i32);
+                    let coord = Point::new(x as i32, y as i32);
                     positions_by_type.entry(c).or_insert(vec![]).push(coord);
                 }
             }
@@ -56,23 +57,24 @@ pub fn part_two(input: &str) -> Option<u32> {
     let width = input.lines().next()?.len() as i32;
     let height = input.lines().count() as i32;
 
-    let is_in_bounds = |coord: &Complex<i32>| is_in_bounds(coord, width, height);
-
-    positions_by_type
-        .values()
-        .flat_map(|coords| coords.iter().permutations(2))
-        .map(|coords| (coords[0], coords[1]))
-        .flat_map(|(coord1, coord2)| {
-            let delta = *coord1 - *coord2;
-            (0..)
-                .map(|x| coord1 + delta * x)
-                .take_while(&is_in_bounds)
-                .collect::<Vec<_>>()
-        })
-        .unique()
-        .count()
-        .try_into()
-        .ok()
+    let is_in_bounds = |coord: &Point| is_in_bounds(coord, width, height);
+
+    let mut antinodes = BitGrid::new(width as usize, height as usize);
+    antinodes.extend(
+        positions_by_type
+            .values()
+            .flat_map(|coords| coords.iter().permutations(2))
+            .map(|coords| (coords[0], coords[1]))
+            .flat_map(|(coord1, coord2)| {
+                let delta = *coord1 - *coord2;
+                (0..)
+                    .map(|x| *coord1 + delta * x)
+                    .take_while(&is_in_bounds)
+                    .collect::<Vec<_>>()
+            }),
+    );
+
+    antinodes.len().try_into().ok()
 }
 
 #[cfg(test)]
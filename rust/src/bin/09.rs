@@ -1,11 +1,11 @@
-use std::vec;
+use advent_of_code::interval::{checksum, Allocator, Extent};
 
 advent_of_code::solution!(9);
 
 const FREE: i32 = -1;
 
 fn segment_to_array(sector_id: usize, size: u32) -> Vec<i32> {
-    if sector_id % 2 == 0 {
+    if sector_id.is_multiple_of(2) {
         vec![(sector_id / 2) as i32; size as usize]
     } else {
         vec![FREE; size as usize]
@@ -51,95 +51,39 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let segments = parse(input);
-
-    let mut defragmented_segments = segments.clone();
-
-    for segment in segments.iter().rev().filter(|seg| seg.id.is_some()) {
-        let free_space = defragmented_segments
-            .iter()
-            .enumerate()
-            .find(|(_, seg)| seg.id.is_none() && seg.size >= segment.size);
-
-        match free_space {
-            None => continue,
-            Some((free_idx, free_space)) => {
-                let (allocated, remainder) = free_space.allocate(segment).ok()?;
-                defragmented_segments[free_idx] = allocated;
-                if remainder.size > 0 {
-                    defragmented_segments.insert(free_idx + 1, remainder);
-                }
-
-                if let Some(idx) = defragmented_segments
-                    .iter()
-                    .rposition(|seg| seg.id == segment.id)
-                {
-                    defragmented_segments[idx].id = None;
-                }
+    let (files, mut free) = parse(input);
+    let mut compacted = Vec::new();
+
+    for &(id, file) in files.iter().rev() {
+        match free.allocate_before(file.len, file.start) {
+            Some(extent) => {
+                free.release(file);
+                compacted.push((id, extent));
             }
+            None => compacted.push((id, file)),
         }
     }
 
-    let mut checksum = 0;
-
-    defragmented_segments.iter().fold(0, |offset, segment| {
-        if let Some(id) = segment.id {
-            let start = offset;
-            let end = offset + segment.size as u64 - 1;
-            checksum += id as u64 * (start + end) * segment.size as u64 / 2;
-        }
-        offset + segment.size as u64
-    });
-
-    Some(checksum)
-}
-
-#[derive(Clone)]
-struct Segment {
-    id: Option<i32>,
-    size: u32,
+    Some(checksum(compacted))
 }
 
-fn parse(input: &str) -> Vec<Segment> {
-    input
-        .trim()
-        .char_indices()
-        .map(|(i, c)| match i % 2 {
-            0 => Segment {
-                id: Some((i / 2) as i32),
-                size: c.to_digit(10).unwrap(),
-            },
-            _ => Segment {
-                id: None,
-                size: c.to_digit(10).unwrap(),
-            },
-        })
-        .collect()
-}
-
-#[derive(Debug)]
-enum SegmentError {
-    InsufficientSpace,
-    NotFree,
-}
-
-impl Segment {
-    fn allocate(&self, new_segment: &Segment) -> Result<(Segment, Segment), SegmentError> {
-        match (self.id, self.size >= new_segment.size) {
-            (None, true) => Ok((
-                Segment {
-                    id: new_segment.id,
-                    size: new_segment.size,
-                },
-                Segment {
-                    id: None,
-                    size: self.size - new_segment.size,
-                },
-            )),
-            (_, true) => Err(SegmentError::NotFree),
-            (_, false) => Err(SegmentError::InsufficientSpace),
+/// Splits the disk map into the files with their ids, and the free space between them.
+fn parse(input: &str) -> (Vec<(u64, Extent)>, Allocator) {
+    let mut files = Vec::new();
+    let mut free = Allocator::new();
+    let mut position = 0;
+
+    for (i, c) in input.trim().char_indices() {
+        let extent = Extent::new(position, c.to_digit(10).unwrap() as u64);
+        if i % 2 == 0 {
+            files.push(((i / 2) as u64, extent));
+        } else {
+            free.release(extent);
         }
+        position = extent.end();
     }
+
+    (files, free)
 }
 
 #[cfg(test)]
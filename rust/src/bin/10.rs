@@ -1,17 +1,10 @@
 use std::collections::VecDeque;
 
-use num_complex::Complex;
+use advent_of_code::geometry::Point;
 use std::ops::Range;
 
 advent_of_code::solution!(10);
 
-const DIRECTIONS: [Complex<i32>; 4] = [
-    Complex::new(0, 1),
-    Complex::new(1, 0),
-    Complex::new(0, -1),
-    Complex::new(-1, 0),
-];
-
 struct Map {
     data: Vec<u8>,
     offset: usize,
@@ -38,33 +31,33 @@ impl Map {
         }
     }
 
-    fn get_altitude(&self, point: &Complex<i32>) -> Option<u8> {
+    fn get_altitude(&self, point: &Point) -> Option<u8> {
         let index = self.point_to_index(point)?;
         Some(self.data[index])
     }
 
-    fn check_bounds(&self, point: &Complex<i32>) -> bool {
-        self.range_x.contains(&point.re) && self.range_y.contains(&point.im)
+    fn check_bounds(&self, point: &Point) -> bool {
+        self.range_x.contains(&point.x) && self.range_y.contains(&point.y)
     }
 
-    fn index_to_point(&self, index: usize) -> Complex<i32> {
+    fn index_to_point(&self, index: usize) -> Point {
         let x = index % self.offset;
         let y = index / self.offset;
-        Complex::new(x as i32, y as i32)
+        Point::new(x as i32, y as i32)
     }
 
-    fn point_to_index(&self, point: &Complex<i32>) -> Option<usize> {
+    fn point_to_index(&self, point: &Point) -> Option<usize> {
         if !self.check_bounds(point) {
             return None;
         }
-        let x = point.re as usize;
-        let y = point.im as usize;
+        let x = point.x as usize;
+        let y = point.y as usize;
         let index = y * self.offset + x;
         Some(index)
     }
 }
 
-fn find_trails_exlude_visited(map: &Map, start: Complex<i32>) -> u32 {
+fn find_trails_exlude_visited(map: &Map, start: Point) -> u32 {
     let mut counter = 0;
     let mut to_visit = VecDeque::from([start]);
     let mut visited = vec![false; map.data.len()];
@@ -85,8 +78,7 @@ fn find_trails_exlude_visited(map: &Map, start: Complex<i32>) -> u32 {
             Some(altitude) => altitude,
         };
 
-        for direction in DIRECTIONS.iter() {
-            let next = current + direction;
+        for next in current.neighbors() {
             if let Some(altitude) = map.get_altitude(&next) {
                 if altitude == expected_altitude {
                     to_visit.push_back(next);
@@ -97,15 +89,14 @@ fn find_trails_exlude_visited(map: &Map, start: Complex<i32>) -> u32 {
     counter
 }
 
-fn find_trails(map: &Map, start: Complex<i32>) -> u32 {
+fn find_trails(map: &Map, start: Point) -> u32 {
     let mut counter = 0;
     let mut to_visit = VecDeque::from([start]);
 
     while let Some(current) = to_visit.pop_back() {
         let expected_altitude = map.get_altitude(&current).unwrap() + 1;
 
-        for d in DIRECTIONS.iter() {
-            let next = current + d;
+        for next in current.neighbors() {
             match map.get_altitude(&next) {
                 Some(9) if 9 == expected_altitude => counter += 1,
                 Some(altitude) if altitude == expected_altitude => to_visit.push_back(next),
@@ -1,13 +1,14 @@
+use advent_of_code::geometry::{Direction, Point};
 use itertools::Itertools;
-use num_complex::Complex;
 
 advent_of_code::solution!(12);
 
-const DIRECTIONS: [Complex<i32>; 4] = [
-    Complex::new(0, 1),
-    Complex::new(1, 0),
-    Complex::new(0, -1),
-    Complex::new(-1, 0),
+/// Vertical and horizontal directions alternate, which `count_sides` relies on.
+const DIRECTIONS: [Direction; 4] = [
+    Direction::South,
+    Direction::East,
+    Direction::North,
+    Direction::West,
 ];
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -19,9 +20,8 @@ pub fn part_one(input: &str) -> Option<u32> {
     let height = map.len() as i32;
     let width = map[0].len() as i32;
 
-    let is_in_bounds = |coord: &Complex<i32>| {
-        0 <= coord.re && coord.re < width && 0 <= coord.im && coord.im < height
-    };
+    let is_in_bounds =
+        |coord: &Point| 0 <= coord.x && coord.x < width && 0 <= coord.y && coord.y < height;
 
     let mut visited = vec![vec![false; width as usize]; height as usize];
     // let edges = vec![vec![false; width as usize + 1]; height as usize + 1];
@@ -33,7 +33,7 @@ pub fn part_one(input: &str) -> Option<u32> {
             }
             total_price += calculate_price(
                 &map,
-                Complex::new(x as i32, y as i32),
+                Point::new(x as i32, y as i32),
                 &mut visited,
                 is_in_bounds,
             );
@@ -51,9 +51,8 @@ pub fn part_two(input: &str) -> Option<u32> {
     let height = map.len() as i32;
     let width = map[0].len() as i32;
 
-    let is_in_bounds = |coord: &Complex<i32>| {
-        0 <= coord.re && coord.re < width && 0 <= coord.im && coord.im < height
-    };
+    let is_in_bounds =
+        |coord: &Point| 0 <= coord.x && coord.x < width && 0 <= coord.y && coord.y < height;
 
     let mut visited = vec![vec![false; width as usize]; height as usize];
     // let edges = vec![vec![false; width as usize + 1]; height as usize + 1];
@@ -65,7 +64,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             }
             total_price += calculate_price_with_discount(
                 &map,
-                Complex::new(x as i32, y as i32),
+                Point::new(x as i32, y as i32),
                 &mut visited,
                 is_in_bounds,
             );
@@ -76,35 +75,35 @@ pub fn part_two(input: &str) -> Option<u32> {
 
 fn calculate_price(
     map: &[Vec<char>],
-    start: Complex<i32>,
+    start: Point,
     visited: &mut [Vec<bool>],
-    bounds_predicate: impl Fn(&Complex<i32>) -> bool,
+    bounds_predicate: impl Fn(&Point) -> bool,
 ) -> u32 {
     let mut area = 1;
     let mut perimeter = 0;
     let mut queue = vec![start];
 
-    visited[start.im as usize][start.re as usize] = true;
+    visited[start.y as usize][start.x as usize] = true;
 
     while let Some(current) = queue.pop() {
-        let current_type = map[current.im as usize][current.re as usize];
+        let current_type = map[current.y as usize][current.x as usize];
         for direction in DIRECTIONS.iter() {
-            let next = current + direction;
+            let next = current.step(*direction);
 
             if !bounds_predicate(&next) {
                 perimeter += 1;
                 continue;
             }
 
-            let next_type = map[next.im as usize][next.re as usize];
+            let next_type = map[next.y as usize][next.x as usize];
 
             if next_type != current_type {
                 perimeter += 1;
                 continue;
             }
 
-            if !visited[next.im as usize][next.re as usize] {
-                visited[next.im as usize][next.re as usize] = true;
+            if !visited[next.y as usize][next.x as usize] {
+                visited[next.y as usize][next.x as usize] = true;
                 queue.push(next);
                 area += 1;
             }
@@ -115,49 +114,49 @@ fn calculate_price(
 
 fn calculate_price_with_discount(
     map: &[Vec<char>],
-    start: Complex<i32>,
+    start: Point,
     visited: &mut [Vec<bool>],
-    bounds_predicate: impl Fn(&Complex<i32>) -> bool,
+    bounds_predicate: impl Fn(&Point) -> bool,
 ) -> u32 {
     let mut area = 1;
     let mut queue = vec![start];
     let mut local_visited = vec![vec![false; map[0].len()]; map.len()];
 
-    visited[start.im as usize][start.re as usize] = true;
-    local_visited[start.im as usize][start.re as usize] = true;
+    visited[start.y as usize][start.x as usize] = true;
+    local_visited[start.y as usize][start.x as usize] = true;
 
     let longer_size = map.len().max(map[0].len());
 
     let mut edges = vec![vec![vec![false; longer_size + 1]; longer_size + 1]; 4];
 
     while let Some(current) = queue.pop() {
-        let current_type = map[current.im as usize][current.re as usize];
+        let current_type = map[current.y as usize][current.x as usize];
         for (i, direction) in DIRECTIONS.iter().enumerate() {
-            let next = current + direction;
+            let next = current.step(*direction);
 
             if !bounds_predicate(&next) {
                 if i % 2 == 0 {
-                    edges[i][current.im as usize][current.re as usize] = true;
+                    edges[i][current.y as usize][current.x as usize] = true;
                 } else {
-                    edges[i][current.re as usize][next.im as usize] = true;
+                    edges[i][current.x as usize][next.y as usize] = true;
                 }
                 continue;
             }
 
-            let next_type = map[next.im as usize][next.re as usize];
+            let next_type = map[next.y as usize][next.x as usize];
 
             if next_type != current_type {
                 if i % 2 == 0 {
-                    edges[i][current.im as usize][current.re as usize] = true;
+                    edges[i][current.y as usize][current.x as usize] = true;
                 } else {
-                    edges[i][current.re as usize][next.im as usize] = true;
+                    edges[i][current.x as usize][next.y as usize] = true;
                 }
                 continue;
             }
 
-            if !visited[next.im as usize][next.re as usize] {
-                visited[next.im as usize][next.re as usize] = true;
-                local_visited[next.im as usize][next.re as usize] = true;
+            if !visited[next.y as usize][next.x as usize] {
+                visited[next.y as usize][next.x as usize] = true;
+                local_visited[next.y as usize][next.x as usize] = true;
                 queue.push(next);
                 area += 1;
             }
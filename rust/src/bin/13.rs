@@ -1,5 +1,8 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use advent_of_code::{
+    error::{AocError, OrRaise},
+    math::{solve_2x2, solve_diophantine, Solution},
+    parse::{ints, sections},
+};
 
 use std::str::FromStr;
 
@@ -31,12 +34,6 @@ pub fn part_two(input: &str) -> Option<u64> {
     Some(total)
 }
 
-const PATTERN: &str =
-    r"Button A: X\+(\d+), Y\+(\d+)\s+Button B: X\+(\d+), Y\+(\d+)\s+Prize: X=(\d+), Y=(\d+)";
-
-lazy_static! {
-    static ref game_regex: Regex = Regex::new(PATTERN).unwrap();
-}
 #[derive(Debug)]
 struct Game {
     a: (i64, i64),
@@ -44,28 +41,20 @@ struct Game {
     prize: (i64, i64),
 }
 
-#[derive(Debug)]
-enum GameError {
-    ParseGameError,
-}
-
 impl FromStr for Game {
-    type Err = GameError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captured_numbers = game_regex
-            .captures(s)
-            .ok_or(GameError::ParseGameError)?
-            .iter()
-            .skip(1)
-            .map(|capture| {
-                capture
-                    .ok_or(GameError::ParseGameError)?
-                    .as_str()
-                    .parse::<i64>()
-                    .map_err(|_| GameError::ParseGameError)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let captured_numbers: Vec<i64> = ints(s).collect();
+        if captured_numbers.len() != 6 {
+            return Err(AocError::at(
+                s,
+                format!(
+                    "expected 6 numbers for a claw machine, found {}",
+                    captured_numbers.len()
+                ),
+            ));
+        }
 
         let game = Game {
             a: (captured_numbers[0], captured_numbers[1]),
@@ -77,28 +66,64 @@ impl FromStr for Game {
 }
 
 fn parse_input(input: &str) -> Vec<Game> {
-    input
-        .split("\n\n")
-        .map(|game_string| Game::from_str(game_string).unwrap())
+    sections(input)
+        .map(|game_string| Game::from_str(game_string).or_raise())
         .collect()
 }
 
+const COST_A: i64 = 3;
+const COST_B: i64 = 1;
+
 fn find_minimum(game: Game) -> Option<u64> {
-    let b_nominator = game.prize.1 * game.a.0 - game.prize.0 * game.a.1;
-    let b_denominator = game.a.0 * game.b.1 - game.b.0 * game.a.1;
-    let b_result = if b_nominator % b_denominator == 0 {
-        b_nominator / b_denominator
-    } else {
-        return None;
+    let matrix = [[game.a.0, game.b.0], [game.a.1, game.b.1]];
+
+    let (a, b) = match solve_2x2(matrix, [game.prize.0, game.prize.1]) {
+        Solution::Unique([a, b]) => (a, b),
+        Solution::Infinite => find_cheapest_on_line(&game)?,
+        Solution::None => return None,
     };
 
-    let a_result = if (game.prize.0 - game.b.0 * b_result) % game.a.0 == 0 {
-        (game.prize.0 - game.b.0 * b_result) / game.a.0
-    } else {
+    if a < 0 || b < 0 {
         return None;
+    }
+    Some((a * COST_A + b * COST_B) as u64)
+}
+
+/// Both buttons move the claw along the same line, so every press count on that line is a candidate.
+fn find_cheapest_on_line(game: &Game) -> Option<(i64, i64)> {
+    let (a, b, prize) = if game.a.0 != 0 || game.b.0 != 0 {
+        (game.a.0, game.b.0, game.prize.0)
+    } else {
+        (game.a.1, game.b.1, game.prize.1)
     };
 
-    Some((a_result * 3 + b_result) as u64)
+    let solutions = solve_diophantine(a, b, prize)?;
+
+    // presses of a are `x + k * step_x`, presses of b are `y - k * step_y`. Both must not be negative.
+    let mut range = (i64::MIN, i64::MAX);
+    restrict(&mut range, solutions.x, solutions.step_x);
+    restrict(&mut range, solutions.y, -solutions.step_y);
+
+    if range.0 > range.1 {
+        return None;
+    }
+
+    // the cost changes linearly with k, so the cheapest solution is at one end of the range.
+    [range.0, range.1]
+        .into_iter()
+        .filter(|k| *k != i64::MIN && *k != i64::MAX)
+        .map(|k| solutions.at(k))
+        .min_by_key(|(a, b)| a * COST_A + b * COST_B)
+}
+
+/// Restricts the range of `k` to `value + k * step >= 0`.
+fn restrict(range: &mut (i64, i64), value: i64, step: i64) {
+    match step.signum() {
+        1 => range.0 = range.0.max(-(value.div_euclid(step))),
+        -1 => range.1 = range.1.min(value.div_euclid(-step)),
+        _ if value < 0 => *range = (1, 0),
+        _ => {}
+    }
 }
 
 #[cfg(test)]
@@ -116,4 +141,14 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(875318608908));
     }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let game = Game {
+            a: (1, 1),
+            b: (3, 3),
+            prize: (10, 10),
+        };
+        assert_eq!(find_minimum(game), Some(6));
+    }
 }
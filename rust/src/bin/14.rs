@@ -1,8 +1,12 @@
 use std::str::FromStr;
 
-use lazy_static::lazy_static;
-use num_complex::Complex;
-use regex::Regex;
+use advent_of_code::{
+    error::{AocError, OrRaise},
+    geometry::Point,
+    math,
+    parse::{ints, lines_as},
+    viz::{self, Canvas, Cell, Color, Format},
+};
 advent_of_code::solution!(14);
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -17,9 +21,9 @@ pub fn part_one(input: &str) -> Option<u32> {
         (div_ceil(height, 2)..height, div_ceil(width, 2)..width),
     ];
 
-    let which_quadrant = |position: Complex<i32>| -> Option<usize> {
+    let which_quadrant = |position: Point| -> Option<usize> {
         for (i, (y_range, x_range)) in quadrants.iter().enumerate() {
-            if y_range.contains(&position.im) && x_range.contains(&position.re) {
+            if y_range.contains(&position.y) && x_range.contains(&position.x) {
                 return Some(i);
             }
         }
@@ -40,110 +44,95 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut robots = parse_input(input);
+    let robots = parse_input(input);
     let width = 101;
     let height = 103;
 
-    let mut i = 0;
-
-    let mut input = String::new();
-
-    loop {
+    // the x coordinates repeat every `width` seconds and the y coordinates every `height`
+    // seconds. The robots that form the tree cluster in both, so the second with the tree is the
+    // one that agrees with the tightest x and the tightest y cluster.
+    let positions_at = |second| {
         robots
-            .iter_mut()
-            .for_each(|robot| robot.move_step(width, height));
-        i += 1;
-        let map = display_grid(&robots, width, height);
-
-        if detect_image(&map) {
-            println!("{}", map);
-            println!("{:>0width$}", i, width = width as usize);
-
-            std::io::stdin().read_line(&mut input).unwrap();
+            .iter()
+            .map(move |robot| robot.position_at_time(second, width, height))
+    };
+    let x_second = tightest_cluster(width, |second| positions_at(second).map(|p| p.x));
+    let y_second = tightest_cluster(height, |second| positions_at(second).map(|p| p.y));
+    let (second, _) = math::crt([
+        (x_second as i64, width as i64),
+        (y_second as i64, height as i64),
+    ])?;
+
+    if viz::enabled() {
+        let positions: Vec<Point> = positions_at(second as i32).collect();
+        let canvas = draw(&positions, width, height);
+        println!("{}", canvas.to_ansi());
+        // the clusters are only the tree if they contain its outline.
+        if !detect_image(&canvas.to_string()) {
+            eprintln!("the robots at second {second} do not draw a tree outline");
         }
+        let _ = canvas.save("day14-tree", Format::Png, 4);
     }
+    Some(second as u32)
 }
 
-const PATTERN: &str = r"p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)";
-
-lazy_static! {
-    static ref game_regex: Regex = Regex::new(PATTERN).unwrap();
-}
-#[derive(Debug)]
-struct Robot {
-    start_position: Complex<i32>,
-    current_position: Complex<i32>,
-    velocity: Complex<i32>,
+/// The second in `0..period` at which the coordinates have the lowest variance.
+fn tightest_cluster<I>(period: i32, coordinates: impl Fn(i32) -> I) -> i32
+where
+    I: Iterator<Item = i32>,
+{
+    (0..period)
+        .min_by_key(|&second| {
+            // `n² * variance`, which stays an integer.
+            let (n, sum, squares) = coordinates(second).fold((0, 0, 0), |(n, sum, squares), c| {
+                let c = c as i64;
+                (n + 1, sum + c, squares + c * c)
+            });
+            n * squares - sum * sum
+        })
+        .unwrap_or(0)
 }
 
 #[derive(Debug)]
-enum RobotError {
-    ParsingError,
+struct Robot {
+    start_position: Point,
+    velocity: Point,
 }
 
 impl FromStr for Robot {
-    type Err = RobotError;
+    type Err = AocError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captured_numbers = game_regex
-            .captures(s)
-            .ok_or(RobotError::ParsingError)?
-            .iter()
-            .skip(1)
-            .map(|capture| {
-                capture
-                    .ok_or(RobotError::ParsingError)?
-                    .as_str()
-                    .parse::<i32>()
-                    .map_err(|_| RobotError::ParsingError)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let captured_numbers: Vec<i32> = ints(s).collect();
+        if captured_numbers.len() != 4 {
+            return Err(AocError::at(s, "expected a robot like `p=0,4 v=3,-3`"));
+        }
 
         let robot = Robot {
-            start_position: Complex::new(captured_numbers[0], captured_numbers[1]),
-            current_position: Complex::new(captured_numbers[0], captured_numbers[1]),
-            velocity: Complex::new(captured_numbers[2], captured_numbers[3]),
+            start_position: Point::new(captured_numbers[0], captured_numbers[1]),
+            velocity: Point::new(captured_numbers[2], captured_numbers[3]),
         };
         Ok(robot)
     }
 }
 
 impl Robot {
-    fn position_at_time(&self, time: i32, width: i32, height: i32) -> Complex<i32> {
-        let position = self.velocity * time + self.start_position;
-        let x = position.re.rem_euclid(width);
-        let y = position.im.rem_euclid(height);
-        Complex::new(x, y)
-    }
-
-    fn move_step(&mut self, width: i32, height: i32) {
-        let position = self.velocity + self.current_position;
-        let x = position.re.rem_euclid(width);
-        let y = position.im.rem_euclid(height);
-        self.current_position = Complex::new(x, y);
+    fn position_at_time(&self, time: i32, width: i32, height: i32) -> Point {
+        (self.velocity * time + self.start_position).rem_euclid(width, height)
     }
 }
 
 fn parse_input(input: &str) -> Vec<Robot> {
-    input
-        .lines()
-        .map(|line| line.trim())
-        .map(|line| line.parse().unwrap())
-        .collect()
+    lines_as(input).or_raise()
 }
 
 fn div_ceil(a: i32, b: i32) -> i32 {
     (a + b - 1) / b
 }
 
-fn display_grid(robots: &[Robot], width: i32, height: i32) -> String {
-    let mut grid = vec![vec![' '; width as usize]; height as usize];
-    for robot in robots {
-        grid[robot.current_position.im as usize][robot.current_position.re as usize] = '#';
-    }
-    grid.iter()
-        .map(|row| row.iter().collect::<String>())
-        .collect::<Vec<_>>()
-        .join("\n")
+fn draw(positions: &[Point], width: i32, height: i32) -> Canvas {
+    let mut canvas = Canvas::new(width as usize, height as usize);
+    canvas.overlay(positions.iter().copied(), Cell::colored('#', Color::GREEN));
+    canvas
 }
 
 fn detect_image(map: &str) -> bool {
@@ -170,4 +159,22 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(12));
     }
+
+    #[test]
+    fn test_part_two() {
+        // the example never forms a tree, so the robots gather into a 5x5 block instead.
+        let second = 6577;
+        let input: String = (0..25)
+            .map(|i| {
+                let velocity = Point::new(i * 7 % 101 - 50, i * 11 % 103 - 51);
+                let block = Point::new(40 + i % 5, 50 + i / 5);
+                let start = (block - velocity * second).rem_euclid(101, 103);
+                format!(
+                    "p={},{} v={},{}\n",
+                    start.x, start.y, velocity.x, velocity.y
+                )
+            })
+            .collect();
+        assert_eq!(part_two(&input), Some(second as u32));
+    }
 }
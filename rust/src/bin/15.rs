@@ -1,4 +1,9 @@
-use num_complex::Complex;
+use advent_of_code::{
+    error::AocError,
+    geometry::{Direction, Point},
+    parse::{char_slices, sections},
+    viz::{self, Animation, Canvas, Cell, Color, Format},
+};
 
 advent_of_code::solution!(15);
 
@@ -12,52 +17,64 @@ enum Tile {
     Empty,
 }
 
-const UP: Complex<i32> = Complex::new(0, -1);
-const DOWN: Complex<i32> = Complex::new(0, 1);
-const LEFT: Complex<i32> = Complex::new(-1, 0);
-const RIGHT: Complex<i32> = Complex::new(1, 0);
+impl Tile {
+    fn cell(&self) -> Cell {
+        match self {
+            Tile::Wall => Cell::colored('#', Color::GRAY),
+            Tile::Robot => Cell::colored('@', Color::YELLOW),
+            Tile::Box => Cell::colored('O', Color::ORANGE),
+            Tile::BoxL => Cell::colored('[', Color::ORANGE),
+            Tile::BoxR => Cell::colored(']', Color::ORANGE),
+            Tile::Empty => Cell::new('.'),
+        }
+    }
+}
+
+const UP: Point = Direction::North.offset();
+const DOWN: Point = Direction::South.offset();
+const LEFT: Point = Direction::West.offset();
+const RIGHT: Point = Direction::East.offset();
 
 fn parse_input(
     input: &str,
-    map_parser: impl Fn(&str) -> (Vec<Vec<Tile>>, Complex<i32>),
-) -> (Vec<Vec<Tile>>, Vec<Complex<i32>>, Complex<i32>) {
-    let (map_raw, movements) = input.split_once("\n\n").unwrap();
+    map_parser: impl Fn(&str) -> (Vec<Vec<Tile>>, Point),
+) -> (Vec<Vec<Tile>>, Vec<Point>, Point) {
+    let mut sections = sections(input);
+    let (Some(map_raw), Some(movements)) = (sections.next(), sections.next()) else {
+        AocError::new("expected a map and a list of movements").raise()
+    };
 
     let (map, robot_pos) = map_parser(map_raw);
 
-    let movements = movements
-        .chars()
-        .filter(|c| *c != '\n')
-        .map(|c| match c {
-            '^' => UP,
-            'v' => DOWN,
-            '<' => LEFT,
-            '>' => RIGHT,
-            _ => panic!("Invalid movement"),
+    let movements = char_slices(movements)
+        .filter(|(c, _)| !c.is_whitespace())
+        .map(|(c, slice)| match Direction::try_from(c) {
+            Ok(direction) => direction.offset(),
+            Err(_) => AocError::at(slice, format!("invalid movement {c:?}")).raise(),
         })
         .collect();
 
     (map, movements, robot_pos)
 }
 
-fn parse_map_regular(input: &str) -> (Vec<Vec<Tile>>, Complex<i32>) {
-    let mut robot_pos = Complex::new(0, 0);
+fn parse_map_regular(input: &str) -> (Vec<Vec<Tile>>, Point) {
+    let mut robot_pos = Point::new(0, 0);
 
     let map = input
         .lines()
         .enumerate()
         .map(|(y, l)| {
-            l.chars()
+            char_slices(l)
                 .enumerate()
-                .map(|(x, c)| match c {
+                .map(|(x, (c, slice))| match c {
                     '#' => Tile::Wall,
                     '@' => {
-                        robot_pos = Complex::new(x as i32, y as i32);
+                        robot_pos = Point::new(x as i32, y as i32);
                         Tile::Robot
                     }
                     'O' => Tile::Box,
                     '.' => Tile::Empty,
-                    _ => panic!("Invalid tile"),
+                    _ => AocError::at(slice, format!("invalid tile {c:?}")).raise(),
                 })
                 .collect()
         })
@@ -66,24 +83,24 @@ fn parse_map_regular(input: &str) -> (Vec<Vec<Tile>>, Complex<i32>) {
     (map, robot_pos)
 }
 
-fn parse_map_heavy(input: &str) -> (Vec<Vec<Tile>>, Complex<i32>) {
-    let mut robot_pos = Complex::new(0, 0);
+fn parse_map_heavy(input: &str) -> (Vec<Vec<Tile>>, Point) {
+    let mut robot_pos = Point::new(0, 0);
 
     let map = input
         .lines()
         .enumerate()
         .map(|(y, l)| {
-            l.chars()
+            char_slices(l)
                 .enumerate()
-                .flat_map(|(x, c)| match c {
+                .flat_map(|(x, (c, slice))| match c {
                     '#' => [Tile::Wall, Tile::Wall],
                     '@' => {
-                        robot_pos = Complex::new(x as i32 * 2, y as i32);
+                        robot_pos = Point::new(x as i32 * 2, y as i32);
                         [Tile::Robot, Tile::Empty]
                     }
                     'O' => [Tile::BoxL, Tile::BoxR],
                     '.' => [Tile::Empty, Tile::Empty],
-                    _ => panic!("Invalid tile"),
+                    _ => AocError::at(slice, format!("invalid tile {c:?}")).raise(),
                 })
                 .collect()
         })
@@ -91,7 +108,7 @@ fn parse_map_heavy(input: &str) -> (Vec<Vec<Tile>>, Complex<i32>) {
     (map, robot_pos)
 }
 
-fn try_move(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i32>) -> bool {
+fn try_move(map: &mut [Vec<Tile>], position: Point, movement: Point) -> bool {
     if can_move(map, position, movement) {
         move_self(map, position, movement);
         true
@@ -100,10 +117,10 @@ fn try_move(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i32
     }
 }
 
-fn can_move(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i32>) -> bool {
+fn can_move(map: &mut [Vec<Tile>], position: Point, movement: Point) -> bool {
     let next = position + movement;
 
-    let next_object = &map[next.im as usize][next.re as usize];
+    let next_object = &map[next.y as usize][next.x as usize];
 
     match (next_object, movement) {
         (Tile::Wall, _) => false,
@@ -119,10 +136,10 @@ fn can_move(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i32
     }
 }
 
-fn move_self(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i32>) {
+fn move_self(map: &mut [Vec<Tile>], position: Point, movement: Point) {
     let next = position + movement;
 
-    let next_object = &map[next.im as usize][next.re as usize];
+    let next_object = &map[next.y as usize][next.x as usize];
 
     match (next_object, movement) {
         (Tile::Wall, _) => panic!("Can't move into wall"),
@@ -147,9 +164,9 @@ fn move_self(map: &mut [Vec<Tile>], position: Complex<i32>, movement: Complex<i3
     }
 }
 
-fn move_to_empty(map: &mut [Vec<Tile>], position: Complex<i32>, next: Complex<i32>) {
-    map[next.im as usize][next.re as usize] = map[position.im as usize][position.re as usize];
-    map[position.im as usize][position.re as usize] = Tile::Empty;
+fn move_to_empty(map: &mut [Vec<Tile>], position: Point, next: Point) {
+    map[next.y as usize][next.x as usize] = map[position.y as usize][position.x as usize];
+    map[position.y as usize][position.x as usize] = Tile::Empty;
 }
 
 fn count_coordinates(map: &[Vec<Tile>]) -> u32 {
@@ -164,26 +181,52 @@ fn count_coordinates(map: &[Vec<Tile>]) -> u32 {
     sum
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (mut map, movements, mut robot_pos) = parse_input(input, parse_map_regular);
+/// Moves the robot through the warehouse and returns the final map. With visualization
+/// enabled, the moves are recorded as `target/viz/<name>.gif`.
+fn simulate(
+    mut map: Vec<Vec<Tile>>,
+    movements: Vec<Point>,
+    mut robot_pos: Point,
+    name: &str,
+) -> Vec<Vec<Tile>> {
+    let mut animation = viz::enabled().then(|| Animation::record(name, 30).every(20));
 
     for movement in movements {
         if try_move(&mut map, robot_pos, movement) {
             robot_pos += movement;
         }
+        if let Some(animation) = &mut animation {
+            animation.frame(draw(&map));
+        }
     }
 
-    Some(count_coordinates(&map))
+    if let Some(animation) = animation {
+        println!("{}", draw(&map).to_ansi());
+        let _ = animation.export(Format::Gif, 4);
+    }
+    map
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (mut map, movements, mut robot_pos) = parse_input(input, parse_map_heavy);
-
-    for movement in movements {
-        if try_move(&mut map, robot_pos, movement) {
-            robot_pos += movement;
+fn draw(map: &[Vec<Tile>]) -> Canvas {
+    let mut canvas = Canvas::new(map[0].len(), map.len());
+    for (y, row) in map.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            canvas.set((y, x), tile.cell());
         }
     }
+    canvas
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let (map, movements, robot_pos) = parse_input(input, parse_map_regular);
+    let map = simulate(map, movements, robot_pos, "day15-part1");
+
+    Some(count_coordinates(&map))
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let (map, movements, robot_pos) = parse_input(input, parse_map_heavy);
+    let map = simulate(map, movements, robot_pos, "day15-part2");
 
     Some(count_coordinates(&map))
 }
@@ -1,53 +1,15 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashSet},
+use advent_of_code::{
+    bitset::BitGrid,
+    error::AocError,
+    geometry::Direction,
+    parse::char_slices,
+    search::{dijkstra, Cost, Problem, Search},
+    viz::{self, Canvas, Cell, Color, Format},
 };
-
-use ndarray::{Array, Array1, Array2};
+use ndarray::{Array1, Array2};
 
 advent_of_code::solution!(16);
 
-#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
-
-impl Direction {
-    fn move_from(&self, position: &(usize, usize)) -> (usize, usize) {
-        match self {
-            Self::North => (position.0 - 1, position.1),
-            Self::South => (position.0 + 1, position.1),
-            Self::West => (position.0, position.1 - 1),
-            Self::East => (position.0, position.1 + 1),
-        }
-    }
-
-    fn get_value(&self) -> (i8, i8) {
-        match self {
-            Self::North => (-1, 0),
-            Self::South => (1, 0),
-            Self::West => (0, -1),
-            Self::East => (0, 1),
-        }
-    }
-
-    fn product(&self, other: &Self) -> i8 {
-        let (x1, y1) = self.get_value();
-        let (x2, y2) = other.get_value();
-        x1 * x2 + y1 * y2
-    }
-}
-
-const DIRECTIONS: [Direction; 4] = [
-    Direction::North,
-    Direction::South,
-    Direction::West,
-    Direction::East,
-];
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tile {
     Empty,
@@ -57,140 +19,105 @@ enum Tile {
 }
 
 impl Tile {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Self::Empty,
-            '#' => Self::Wall,
-            'S' => Self::Start,
-            'E' => Self::End,
-            _ => panic!("Invalid tile"),
+            '.' => Some(Self::Empty),
+            '#' => Some(Self::Wall),
+            'S' => Some(Self::Start),
+            'E' => Some(Self::End),
+            _ => None,
+        }
+    }
+
+    fn cell(&self) -> Cell {
+        match self {
+            Self::Empty => Cell::new('.'),
+            Self::Wall => Cell::colored('#', Color::GRAY),
+            Self::Start => Cell::colored('S', Color::YELLOW),
+            Self::End => Cell::colored('E', Color::RED),
         }
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let maze = parse_input(input);
-
-    let start_position = maze
-        .indexed_iter()
-        .find(|(_, &tile)| tile == Tile::Start)
-        .unwrap()
-        .0;
-
-    let (cost, _) = solve_maze(&maze, start_position);
-    cost
+    solve_maze(&maze).cost()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     let maze = parse_input(input);
 
-    let start_position = maze
-        .indexed_iter()
-        .find(|(_, &tile)| tile == Tile::Start)
-        .unwrap()
-        .0;
+    let (height, width) = maze.dim();
+    let mut seats = BitGrid::new(width, height);
+    seats.extend(
+        solve_maze(&maze)
+            .on_optimal_paths()
+            .into_iter()
+            .map(|(position, _)| position),
+    );
 
-    let (_, visited) = solve_maze(&maze, start_position);
-    let seat_count = count_best_paths(&maze, &visited);
-    Some(seat_count)
+    if viz::enabled() {
+        let mut canvas = Canvas::from_array(&maze, |tile| tile.cell());
+        canvas.overlay(seats.iter(), Cell::colored('O', Color::GREEN));
+        println!("{}", canvas.to_ansi());
+        let _ = canvas.save("day16-seats", Format::Png, 4);
+    }
+
+    seats.len().try_into().ok()
 }
 
 fn parse_input(input: &str) -> Array2<Tile> {
     let map: Array1<Tile> = input
         .lines()
-        .flat_map(|line| line.trim().chars().map(Tile::from_char))
+        .flat_map(|line| char_slices(line.trim()))
+        .map(|(c, slice)| {
+            Tile::from_char(c)
+                .unwrap_or_else(|| AocError::at(slice, format!("invalid tile {c:?}")).raise())
+        })
         .collect();
 
     let shape = (
         input.lines().count(),
-        input.lines().next().unwrap().trim().len(),
+        input.lines().next().map_or(0, |line| line.trim().len()),
     );
 
-    map.into_shape_with_order(shape).unwrap()
+    map.into_shape_with_order(shape)
+        .unwrap_or_else(|_| AocError::new("the rows of the map differ in length").raise())
 }
 
-fn solve_maze(maze: &Array2<Tile>, start_position: (usize, usize)) -> (Option<u32>, Array2<u32>) {
-    let mut visited = Array::from_elem(maze.dim(), u32::MAX);
-
-    let mut queue = BinaryHeap::new();
-    queue.push(Reverse((0, start_position, Direction::East)));
-
-    while let Some(Reverse((cost, position, current_direction))) = queue.pop() {
-        if maze[position] == Tile::End {
-            return (Some(cost), visited);
-        }
-        for direction in DIRECTIONS.iter() {
-            let turn_cost =
-                (direction.product(&current_direction) - 1).unsigned_abs() as u32 * 1000;
-
-            if turn_cost == 2000 {
-                continue;
-            }
+/// The reindeer either moves a tile forward or turns on the spot.
+struct Maze<'a>(&'a Array2<Tile>);
 
-            let next_position = direction.move_from(&position);
-            let next_tile = maze[next_position];
+impl Problem for Maze<'_> {
+    type State = ((usize, usize), Direction);
 
-            if next_tile == Tile::Wall {
-                continue;
-            }
+    fn successors(&self, &(position, direction): &Self::State) -> Vec<(Self::State, Cost)> {
+        let mut successors = vec![
+            ((position, direction.turn_left()), 1000),
+            ((position, direction.turn_right()), 1000),
+        ];
 
-            let next_cost = cost + turn_cost + 1;
-            if next_cost < visited[next_position] {
-                visited[next_position] = next_cost;
-                queue.push(Reverse((next_cost, next_position, *direction)));
+        if let Some(next) = direction.step_index(position, self.0.dim()) {
+            if self.0[next] != Tile::Wall {
+                successors.push(((next, direction), 1));
             }
         }
+        successors
+    }
+
+    fn is_goal(&self, &(position, _): &Self::State) -> bool {
+        self.0[position] == Tile::End
     }
-    (None, visited)
 }
 
-fn count_best_paths(maze: &Array2<Tile>, costs: &Array2<u32>) -> u32 {
-    let end_position = maze
+fn solve_maze(maze: &Array2<Tile>) -> Search<((usize, usize), Direction)> {
+    let start_position = maze
         .indexed_iter()
-        .find(|(_, &tile)| tile == Tile::End)
+        .find(|(_, &tile)| tile == Tile::Start)
         .unwrap()
         .0;
 
-    let mut tile_count = 2;
-
-    let mut queue = Vec::new();
-
-    let end_cost = costs[end_position];
-    let mut visited = HashSet::new();
-
-    for direction in DIRECTIONS {
-        let next = direction.move_from(&end_position);
-        let next_cost = costs[next];
-        if next_cost < end_cost {
-            queue.push((next, direction));
-            tile_count += 1;
-            visited.insert(next);
-        }
-    }
-
-    while let Some((position, current_direction)) = queue.pop() {
-        if maze[position] == Tile::Start {
-            continue;
-        }
-        for direction in DIRECTIONS {
-            let product = direction.product(&current_direction);
-            let next_position = direction.move_from(&position);
-
-            if product == -1 || visited.contains(&next_position) {
-                continue;
-            }
-
-            let current_cost = costs[position];
-            let max_next_cost =
-                current_cost + direction.product(&current_direction) as u32 * 1000 - 1;
-            if costs[next_position] <= max_next_cost {
-                queue.push((next_position, direction));
-                tile_count += 1;
-                visited.insert(next_position);
-            }
-        }
-    }
-    tile_count
+    dijkstra(&Maze(maze), [(start_position, Direction::East)])
 }
 
 #[cfg(test)]
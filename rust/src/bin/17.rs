@@ -1,146 +1,70 @@
-use std::result;
-
+use advent_of_code::{
+    error::{parse_at, AocError, OrRaise},
+    viz,
+    vm::{self, Machine, Registers},
+};
 use itertools::Itertools;
 
 advent_of_code::solution!(17);
 
 pub fn part_one(input: &str) -> Option<String> {
-    let lines: Vec<&str> = input.lines().map(|line| line.trim()).collect();
-
-    let registers: Vec<u32> = lines
-        .iter()
-        .take(3)
-        .filter_map(|line| line.split_once(':'))
-        .map(|(_, value)| value.trim().parse().unwrap())
-        .collect();
-    let (a_register, b_register, c_register) = (registers[0], registers[1], registers[2]);
-
-    let mut computer = Computer {
-        a_register,
-        b_register,
-        c_register,
-        ..Default::default()
-    };
-
-    let program_str = lines.iter().last().unwrap();
-    let program: Vec<u8> = program_str
-        .split_once(':')
-        .unwrap()
-        .1
-        .trim()
-        .split(',')
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    let output = computer.execute_program(&program);
-    Some(output.iter().join(","))
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
-    let program: Vec<u8> = input
-        .lines()
-        .last()
-        .unwrap()
-        .split_once(':')
-        .unwrap()
-        .1
-        .trim()
-        .split(',')
-        .map(|x| x.parse().unwrap())
-        .collect();
-
-    None
-}
-
-#[derive(Default, Debug, Clone, PartialEq)]
-struct Computer {
-    a_register: u32,
-    b_register: u32,
-    c_register: u32,
-
-    pointer: u32,
-
-    std_out: Vec<u8>,
-}
-
-impl Computer {
-    fn execute_program(&mut self, program: &[u8]) -> &Vec<u8> {
-        while let (Some(optcode), Some(&operand)) = (
-            program.get(self.pointer as usize),
-            program.get((self.pointer + 1) as usize),
-        ) {
-            let instruction = match optcode {
-                0 => Computer::adv,
-                1 => Computer::bxl,
-                2 => Computer::bst,
-                3 => {
-                    self.jnz(operand);
-                    continue;
-                }
-                4 => Computer::bxc,
-                5 => Computer::out,
-                6 => Computer::bdv,
-                7 => Computer::cdv,
-                8.. => panic!("Unexpected optcode"),
-            };
-
-            instruction(self, operand);
-            self.pointer += 2;
-        }
-        &self.std_out
-    }
-
-    fn combo_to_value(&self, operand: u8) -> u32 {
-        match operand {
-            0..=3 => operand as u32,
-            4 => self.a_register,
-            5 => self.b_register,
-            6 => self.c_register,
-            7.. => panic!("Reseved operand"),
-        }
-    }
+    let (registers, program) = parse_input(input);
 
-    fn adv(&mut self, operand: u8) {
-        self.a_register /= 2_u32.checked_pow(self.combo_to_value(operand)).unwrap();
-        // self.a_register /= 1 << self.combo_to_value(operand);
+    let mut machine = Machine::new(registers).with_step_limit(STEP_LIMIT);
+    if viz::enabled() {
+        machine = machine.traced();
     }
+    machine.run(&program).or_raise();
 
-    fn bxl(&mut self, operand: u8) {
-        self.b_register ^= operand as u32;
+    if viz::enabled() {
+        println!("{}", vm::disassemble(&program));
+        machine.trace().iter().for_each(|step| println!("{step}"));
     }
+    Some(machine.output().iter().join(","))
+}
 
-    fn bst(&mut self, operand: u8) {
-        self.b_register = self.combo_to_value(operand) & 0b111;
-    }
+pub fn part_two(input: &str) -> Option<u64> {
+    let (_, program) = parse_input(input);
+    vm::find_quine(&program)
+}
 
-    fn jnz(&mut self, operand: u8) {
-        if self.a_register != 0 {
-            self.pointer = operand as u32;
-        } else {
-            self.pointer += 2;
+/// Programs are expected to halt long before, this only guards against endless loops.
+const STEP_LIMIT: usize = 1_000_000;
+
+/// Reads the registers, which default to 0, and the program, whose values are all 3-bit numbers.
+fn parse_input(input: &str) -> (Registers, Vec<u8>) {
+    let mut registers = Registers::default();
+    let mut program = None;
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (label, value) = line.split_once(':').unwrap_or_else(|| {
+            AocError::at(
+                line,
+                "expected a line like `Register A: 729` or `Program: 0,1`",
+            )
+            .raise()
+        });
+        let value = value.trim();
+
+        match label {
+            "Register A" => registers.a = parse_at(value).or_raise(),
+            "Register B" => registers.b = parse_at(value).or_raise(),
+            "Register C" => registers.c = parse_at(value).or_raise(),
+            "Program" => {
+                let values = value
+                    .split(',')
+                    .map(|value| match parse_at(value).or_raise() {
+                        number @ 0..8 => number,
+                        _ => AocError::at(value, "expected a 3-bit number").raise(),
+                    });
+                program = Some(values.collect());
+            }
+            _ => AocError::at(label, format!("unknown label {label:?}")).raise(),
         }
     }
 
-    fn bxc(&mut self, _: u8) {
-        self.b_register ^= self.c_register;
-    }
-
-    fn out(&mut self, operand: u8) {
-        let result = self.combo_to_value(operand) & 0b111;
-        self.std_out.push(result as u8);
-    }
-
-    fn bdv(&mut self, operand: u8) {
-        let numerator = self.a_register;
-        let denominator = 2_u32.checked_pow(self.combo_to_value(operand)).unwrap();
-        self.b_register = numerator / denominator;
-    }
-
-    fn cdv(&mut self, operand: u8) {
-        let numerator = self.a_register;
-        let denominator = 2_u32.checked_pow(self.combo_to_value(operand)).unwrap();
-        self.c_register = numerator / denominator;
-    }
+    let program = program.unwrap_or_else(|| AocError::new("the input has no program").raise());
+    (registers, program)
 }
 
 #[cfg(test)]
@@ -1,53 +1,54 @@
-use std::{cmp::Reverse, collections::BinaryHeap};
-
-use ndarray::{Array, Array2};
+use advent_of_code::{
+    disjoint_set,
+    error::{parse_at, AocError, OrRaise},
+    geometry::Direction,
+    search::{bfs, Cost, Problem},
+};
+use ndarray::Array2;
 
 advent_of_code::solution!(18);
 
-const DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 const GRID_DIMENSIONS: (usize, usize) = (7, 7);
 // const GRID_DIMENSIONS: (usize, usize) = (71, 71);
 const START_POSITION: (usize, usize) = (0, 0);
 
+fn parse_input(input: &str) -> Vec<(usize, usize)> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (x, y) = line
+                .split_once(',')
+                .unwrap_or_else(|| AocError::at(line, "expected a position like `5,4`").raise());
+            (parse_at(x).or_raise(), parse_at(y).or_raise())
+        })
+        .collect()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
     let length = 12;
     // let length = 1024;
 
-    let byte_positions: Vec<(usize, usize)> = input
-        .lines()
-        .take(length)
-        .map(|line| line.trim().split_once(',').unwrap())
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .collect();
+    let byte_positions: Vec<(usize, usize)> = parse_input(input).into_iter().take(length).collect();
 
     let map = create_grid(GRID_DIMENSIONS, byte_positions.iter());
     let end_position = (GRID_DIMENSIONS.0 - 1, GRID_DIMENSIONS.1 - 1);
 
-    let (cost, _) = solve_maze(&map, START_POSITION, end_position);
-    cost
+    solve_maze(&map, START_POSITION, end_position)
 }
 
 pub fn part_two(input: &str) -> Option<String> {
+    let byte_positions = parse_input(input);
     let end_position = (GRID_DIMENSIONS.0 - 1, GRID_DIMENSIONS.1 - 1);
-    let byte_positions: Vec<(usize, usize)> = input
-        .lines()
-        .map(|line| line.trim().split_once(',').unwrap())
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .collect();
-
-    let byte_number = (0..byte_positions.len())
-        .collect::<Vec<usize>>()
-        .partition_point(|i| {
-            let map = create_grid(GRID_DIMENSIONS, byte_positions.iter().take(*i));
-            let (cost, _) = solve_maze(&map, START_POSITION, end_position);
-            cost.is_some()
-        })
-        - 1;
-
-    let result: String = byte_positions[byte_number].0.to_string()
-        + ","
-        + &byte_positions[byte_number].1.to_string();
-    Some(result)
+    let byte = disjoint_set::first_separating(
+        GRID_DIMENSIONS,
+        &byte_positions,
+        START_POSITION,
+        end_position,
+    )?;
+    let (x, y) = byte_positions[byte];
+    Some(format!("{x},{y}"))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -66,20 +67,25 @@ fn create_grid<'a>(
     map
 }
 
-fn next_position(
-    position: (usize, usize),
-    direction: (i8, i8),
-    shape: (usize, usize),
-) -> Option<(usize, usize)> {
-    let next_position = (
-        position.0.checked_add_signed(direction.0 as isize)?,
-        position.1.checked_add_signed(direction.1 as isize)?,
-    );
-
-    if next_position.0 < shape.0 && next_position.1 < shape.1 {
-        Some(next_position)
-    } else {
-        None
+struct Memory<'a> {
+    map: &'a Array2<Tile>,
+    end_position: (usize, usize),
+}
+
+impl Problem for Memory<'_> {
+    type State = (usize, usize);
+
+    fn successors(&self, &position: &Self::State) -> Vec<(Self::State, Cost)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| direction.step_index(position, self.map.dim()))
+            .filter(|&next| self.map[next] != Tile::Wall)
+            .map(|next| (next, 1))
+            .collect()
+    }
+
+    fn is_goal(&self, state: &Self::State) -> bool {
+        *state == self.end_position
     }
 }
 
@@ -87,34 +93,12 @@ fn solve_maze(
     maze: &Array2<Tile>,
     start_position: (usize, usize),
     end_position: (usize, usize),
-) -> (Option<u32>, Array2<u32>) {
-    let shape = maze.dim();
-    let mut visited = Array::from_elem(shape, u32::MAX);
-
-    let mut queue = BinaryHeap::new();
-    queue.push(Reverse((0, start_position)));
-
-    while let Some(Reverse((cost, position))) = queue.pop() {
-        if position == end_position {
-            return (Some(cost), visited);
-        }
-        for direction in DIRECTIONS {
-            if let Some(next_position) = next_position(position, direction, shape) {
-                let next_tile = maze[next_position];
-
-                if next_tile == Tile::Wall {
-                    continue;
-                }
-
-                let next_cost = cost + 1;
-                if next_cost < visited[next_position] {
-                    visited[next_position] = next_cost;
-                    queue.push(Reverse((next_cost, next_position)));
-                }
-            }
-        }
-    }
-    (None, visited)
+) -> Option<u32> {
+    let memory = Memory {
+        map: maze,
+        end_position,
+    };
+    bfs(&memory, [start_position]).cost()
 }
 
 #[cfg(test)]
@@ -1,15 +1,16 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
+use std::collections::BTreeMap;
+
+use advent_of_code::{
+    error::AocError,
+    geometry::{Direction, Index},
+    parse::char_slices,
+    search::{bfs, Cost, Problem},
+    viz::{self, Canvas, Cell, Color, Format},
 };
-
-use itertools::Itertools;
 use ndarray::{Array, Array1, Array2};
 
 advent_of_code::solution!(20);
 
-const DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tile {
     Empty,
@@ -19,100 +20,99 @@ enum Tile {
 }
 
 impl Tile {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '.' => Self::Empty,
-            '#' => Self::Wall,
-            'S' => Self::Start,
-            'E' => Self::End,
-            _ => panic!("Invalid tile"),
+            '.' => Some(Self::Empty),
+            '#' => Some(Self::Wall),
+            'S' => Some(Self::Start),
+            'E' => Some(Self::End),
+            _ => None,
+        }
+    }
+
+    fn cell(&self) -> Cell {
+        match self {
+            Self::Empty => Cell::new('.'),
+            Self::Wall => Cell::colored('#', Color::GRAY),
+            Self::Start => Cell::colored('S', Color::YELLOW),
+            Self::End => Cell::colored('E', Color::RED),
         }
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let maze = parse_input(input);
-
-    let visited = solve_maze(&maze)?;
+    count_cheats(input, 2, 100)
+}
 
-    let cheets = find_cheets(&maze, &visited);
+pub fn part_two(input: &str) -> Option<u32> {
+    count_cheats(input, 20, 100)
+}
 
-    // dbg!(&cheets.iter().sorted().collect::<Vec<_>>());
+fn count_cheats(input: &str, radius: usize, min_saving: u32) -> Option<u32> {
+    let maze = parse_input(input);
+    let distances = solve_maze(&maze)?;
 
-    let cheet_count = cheets
-        .iter()
-        .filter(|(&time_saved, &_counter)| time_saved >= 100)
-        .map(|(&_time_saved, &counter)| counter)
-        .sum();
+    if viz::enabled() {
+        let mut canvas = Canvas::from_array(&maze, |tile| tile.cell());
+        if let Some((start, end, _)) = cheats(&distances, radius).max_by_key(|cheat| cheat.2) {
+            canvas.overlay([start, end], Cell::colored('O', Color::GREEN));
+        }
+        println!("{}", canvas.to_ansi());
+        let _ = canvas.save(&format!("day20-cheat-{radius}"), Format::Png, 4);
+    }
 
-    Some(cheet_count)
-}
-
-pub fn part_two(input: &str) -> Option<u32> {
-    None
+    let count: usize = find_cheats(&distances, radius, min_saving).values().sum();
+    count.try_into().ok()
 }
 
 fn parse_input(input: &str) -> Array2<Tile> {
     let map: Array1<Tile> = input
         .lines()
-        .flat_map(|line| line.trim().chars().map(Tile::from_char))
+        .flat_map(|line| char_slices(line.trim()))
+        .map(|(c, slice)| {
+            Tile::from_char(c)
+                .unwrap_or_else(|| AocError::at(slice, format!("invalid tile {c:?}")).raise())
+        })
         .collect();
 
     let shape = (
         input.lines().count(),
-        input.lines().next().unwrap().trim().len(),
+        input.lines().next().map_or(0, |line| line.trim().len()),
     );
 
-    map.into_shape_with_order(shape).unwrap()
+    map.into_shape_with_order(shape)
+        .unwrap_or_else(|_| AocError::new("the rows of the map differ in length").raise())
 }
 
-fn next_position(
-    position: (usize, usize),
-    direction: (i8, i8),
-    shape: (usize, usize),
-) -> Option<(usize, usize)> {
-    let next_position = (
-        position.0.checked_add_signed(direction.0 as isize)?,
-        position.1.checked_add_signed(direction.1 as isize)?,
-    );
+struct Track<'a>(&'a Array2<Tile>);
 
-    if next_position.0 < shape.0 && next_position.1 < shape.1 {
-        Some(next_position)
-    } else {
-        None
+impl Problem for Track<'_> {
+    type State = (usize, usize);
+
+    fn successors(&self, &position: &Self::State) -> Vec<(Self::State, Cost)> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| direction.step_index(position, self.0.dim()))
+            .filter(|&next| self.0[next] != Tile::Wall)
+            .map(|next| (next, 1))
+            .collect()
+    }
+
+    fn is_goal(&self, &position: &Self::State) -> bool {
+        self.0[position] == Tile::End
     }
 }
 
 fn solve_maze(maze: &Array2<Tile>) -> Option<Array2<u32>> {
-    let shape = maze.dim();
     let start_position = find_tile(maze, Tile::Start)?;
-    let mut visited = Array::from_elem(shape, u32::MAX);
-    visited[start_position] = 0;
-
-    let mut queue = BinaryHeap::new();
-    queue.push(Reverse((0, start_position)));
+    let search = bfs(&Track(maze), [start_position]);
+    search.cost()?;
 
-    while let Some(Reverse((cost, position))) = queue.pop() {
-        if maze[position] == Tile::End {
-            return Some(visited);
-        }
-        for direction in DIRECTIONS {
-            if let Some(next_position) = next_position(position, direction, shape) {
-                let next_tile = maze[next_position];
-
-                if next_tile == Tile::Wall {
-                    continue;
-                }
-
-                let next_cost = cost + 1;
-                if next_cost < visited[next_position] {
-                    visited[next_position] = next_cost;
-                    queue.push(Reverse((next_cost, next_position)));
-                }
-            }
-        }
+    let mut distances = Array::from_elem(maze.dim(), u32::MAX);
+    for (&position, cost) in search.distances() {
+        distances[position] = cost;
     }
-    None
+    Some(distances)
 }
 
 fn find_tile(maze: &Array2<Tile>, tile_type: Tile) -> Option<(usize, usize)> {
@@ -121,61 +121,44 @@ fn find_tile(maze: &Array2<Tile>, tile_type: Tile) -> Option<(usize, usize)> {
         .map(|(position, _tile)| position)
 }
 
-fn find_cheets(maze: &Array2<Tile>, visited: &Array2<u32>) -> HashMap<i32, u32> {
-    let mut cheets = HashMap::new();
-
-    let shape = maze.dim();
-    let mut path = Vec::new();
-
-    let mut current_position = find_tile(maze, Tile::Start).unwrap();
-    let end_position = find_tile(maze, Tile::End).unwrap();
-
-    while current_position != end_position {
-        path.push(current_position);
-        let current_section = visited[current_position];
-        let mut next_path_coord = None;
-        for direction in DIRECTIONS {
-            let next_coordinates = next_position(current_position, direction, shape);
-
-            match next_coordinates {
-                Some(next_coord)
-                    if maze[next_coord] == Tile::Empty
-                        && visited[next_coord] == current_section + 1 =>
-                {
-                    next_path_coord = Some(next_coord);
-                    continue;
-                }
-                Some(next_coord) if maze[next_coord] == Tile::Wall => {}
-                _ => {
-                    continue;
-                }
-            }
-
-            let past_next_coordinates =
-                next_position(current_position, (direction.0 * 2, direction.1 * 2), shape);
-
-            let past_next_coordinates = match past_next_coordinates {
-                Some(next_coord) if maze[next_coord] != Tile::Wall => next_coord,
-                _ => continue,
-            };
-
-            let past_next_section = visited[past_next_coordinates];
-
-            let time_saved = past_next_section as i32 - current_section as i32 - 2;
-
-            if time_saved > 0 {
-                cheets
-                    .entry(time_saved)
-                    .and_modify(|counter| *counter += 1)
-                    .or_insert(1);
-            }
-        }
-        match next_path_coord {
-            Some(next) => current_position = next,
-            None => break,
+/// The number of cheats by the time they save, for all cheats that save at least `min_saving`.
+fn find_cheats(distances: &Array2<u32>, radius: usize, min_saving: u32) -> BTreeMap<u32, usize> {
+    let mut histogram = BTreeMap::new();
+    for (_, _, saving) in cheats(distances, radius) {
+        if saving >= min_saving {
+            *histogram.entry(saving).or_insert(0) += 1;
         }
     }
-    cheets
+    histogram
+}
+
+/// All cheats that save time, as their start, their end and the time they save.
+///
+/// A cheat goes from any track position to any other one within a Manhattan distance of
+/// `radius`, regardless of the walls in between, and saves the difference in their distances
+/// from the start minus its own length.
+fn cheats(
+    distances: &Array2<u32>,
+    radius: usize,
+) -> impl Iterator<Item = (Index, Index, u32)> + '_ {
+    let (height, width) = distances.dim();
+
+    distances
+        .indexed_iter()
+        .filter(|(_, &start)| start != u32::MAX)
+        .flat_map(move |((row, column), &start)| {
+            let end_rows = row.saturating_sub(radius)..(row + radius + 1).min(height);
+            end_rows.flat_map(move |end_row| {
+                let rest = radius - row.abs_diff(end_row);
+                let end_columns = column.saturating_sub(rest)..(column + rest + 1).min(width);
+                end_columns.filter_map(move |end_column| {
+                    let end = distances[(end_row, end_column)];
+                    let length = (row.abs_diff(end_row) + column.abs_diff(end_column)) as u32;
+                    (end != u32::MAX && end > start + length)
+                        .then(|| ((row, column), (end_row, end_column), end - start - length))
+                })
+            })
+        })
 }
 
 #[cfg(test)]
@@ -193,4 +176,45 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(0));
     }
+
+    #[test]
+    fn test_find_cheats() {
+        let maze = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let distances = solve_maze(&maze).unwrap();
+
+        let cheats = find_cheats(&distances, 2, 1);
+        let expected = [
+            (2, 14),
+            (4, 14),
+            (6, 2),
+            (8, 4),
+            (10, 2),
+            (12, 3),
+            (20, 1),
+            (36, 1),
+            (38, 1),
+            (40, 1),
+            (64, 1),
+        ];
+        assert_eq!(cheats, BTreeMap::from(expected));
+
+        let cheats = find_cheats(&distances, 20, 50);
+        let expected = [
+            (50, 32),
+            (52, 31),
+            (54, 29),
+            (56, 39),
+            (58, 25),
+            (60, 23),
+            (62, 20),
+            (64, 19),
+            (66, 12),
+            (68, 14),
+            (70, 12),
+            (72, 22),
+            (74, 4),
+            (76, 3),
+        ];
+        assert_eq!(cheats, BTreeMap::from(expected));
+    }
 }
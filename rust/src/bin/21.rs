@@ -1,189 +1,170 @@
-use std::cmp::Ordering;
-
-use itertools::Itertools;
+use advent_of_code::{
+    error::{parse_at, AocError, OrRaise},
+    geometry::Index,
+    memo::Memo,
+};
 use ndarray::{array, Array2};
 
 advent_of_code::solution!(21);
 
-fn num_pad() -> Array2<char> {
-    array![
-        ['7', '8', '9'],
-        ['4', '5', '6'],
-        ['1', '2', '3'],
-        ['#', '0', 'A']
-    ]
-}
+/// The cheapest costs of moves, keyed by the keypad, the move and the depth of the keypad.
+type CostMemo = Memo<(&'static str, char, char, usize), u64>;
 
-fn directional_pad() -> Array2<char> {
-    array![['#', '^', 'A'], ['<', 'v', '>']]
+/// A keypad with a gap, marked `#`, that the robot arm must never point at.
+struct Keypad {
+    /// Tells the keypads apart in memoized costs, which only hold for one layout.
+    name: &'static str,
+    keys: Array2<char>,
+    gap: Index,
 }
 
-const DIRECTION_ORDER: [char; 4] = ['<', 'v', '>', '^'];
-fn dir_pad_comparator(a: &char, b: &char) -> Ordering {
-    let a_pos = DIRECTION_ORDER.iter().position(|c| a == c).unwrap();
-    let b_pos = DIRECTION_ORDER.iter().position(|c| b == c).unwrap();
-    Ord::cmp(&a_pos, &b_pos)
-}
+impl Keypad {
+    fn new(name: &'static str, keys: Array2<char>) -> Self {
+        let gap = keys
+            .indexed_iter()
+            .find(|(_, &key)| key == '#')
+            .expect("the keypad has no gap")
+            .0;
+        Self { name, keys, gap }
+    }
 
-fn move_to_key(
-    from_position: (usize, usize),
-    to_position: (usize, usize),
-    keypad: &Array2<char>,
-) -> Vec<char> {
-    match (keypad.dim().0, from_position, to_position) {
-        (4, (3, from_y), (to_x, 0)) => {
-            let mut steps = Vec::new();
-            steps.push('^');
-            match to_x {
-                1 => {
-                    steps.push('^');
-                }
-                0 => {
-                    steps.push('^');
-                    steps.push('^');
-                }
-                _ => {}
-            }
-            if from_y == 2 {
-                steps.push('<');
-            };
-            steps.push('<');
-            steps.push('A');
-            return steps;
-        }
-        (4, (from_x, 0), (3, to_y)) => {
-            let mut steps = Vec::new();
-            steps.push('>');
-            if to_y == 2 {
-                steps.push('>');
-            }
-            steps.push('v');
-            match from_x {
-                1 => {
-                    steps.push('v');
-                }
-                0 => {
-                    steps.push('v');
-                    steps.push('v');
-                }
-                _ => {}
-            }
+    fn numeric() -> Self {
+        Self::new(
+            "numeric",
+            array![
+                ['7', '8', '9'],
+                ['4', '5', '6'],
+                ['1', '2', '3'],
+                ['#', '0', 'A']
+            ],
+        )
+    }
 
-            steps.push('A');
-            return steps;
+    fn directional() -> Self {
+        Self::new("directional", array![['#', '^', 'A'], ['<', 'v', '>']])
+    }
+
+    fn position(&self, key: char) -> Option<Index> {
+        self.keys
+            .indexed_iter()
+            .find(|(_, &k)| k == key && k != '#')
+            .map(|(position, _)| position)
+    }
+
+    /// The presses on a directional keypad that move the arm from `from` to `to` and press it.
+    ///
+    /// Only moving all the way horizontally, then vertically, or the other way around can be
+    /// cheapest, since repeated presses of the same key cost nothing on the keypad above.
+    fn paths(&self, from: char, to: char) -> Vec<Vec<char>> {
+        let (Some(start), Some(end)) = (self.position(from), self.position(to)) else {
+            panic!("{from:?} or {to:?} is not a key of the keypad");
+        };
+
+        let horizontal = if end.1 < start.1 {
+            vec!['<'; start.1 - end.1]
+        } else {
+            vec!['>'; end.1 - start.1]
+        };
+        let vertical = if end.0 < start.0 {
+            vec!['^'; start.0 - end.0]
+        } else {
+            vec!['v'; end.0 - start.0]
+        };
+
+        let mut paths = Vec::new();
+        // the corner of each path must not be the gap.
+        if (start.0, end.1) != self.gap {
+            paths.push([horizontal.as_slice(), &vertical, &['A']].concat());
         }
-        (2, (0, from_y), (1, 0)) => {
-            let mut steps = Vec::new();
-            steps.push('v');
-            steps.push('<');
-            if from_y == 2 {
-                steps.push('<');
-            }
-            steps.push('A');
-            return steps;
+        if (end.0, start.1) != self.gap {
+            paths.push([vertical.as_slice(), &horizontal, &['A']].concat());
         }
-        _ => {}
+        paths.dedup();
+        paths
     }
+}
 
-    let dx = to_position.1 as isize - from_position.1 as isize;
-    let dy = to_position.0 as isize - from_position.0 as isize;
-
-    let dx_steps = match dx {
-        1.. => vec!['>'; dx as usize],
-        ..=-1 => vec!['<'; -dx as usize],
-        0 => Vec::new(),
-    };
-
-    let dy_steps = match dy {
-        1.. => vec!['v'; dy as usize],
-        ..=-1 => vec!['^'; -dy as usize],
-        0 => Vec::new(),
-    };
-
-    let mut steps: Vec<char> = dx_steps;
-    steps.extend(dy_steps);
-    steps.sort_by(dir_pad_comparator);
-    steps.push('A');
-    steps
+/// The number of presses it takes a human to make the robot, which is `depth` directional
+/// keypads away, move its arm from `from` to `to` and press it.
+fn cost(memo: &mut CostMemo, keypad: &Keypad, from: char, to: char, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    memo.get_or_compute((keypad.name, from, to, depth), |memo| {
+        keypad
+            .paths(from, to)
+            .iter()
+            .map(|path| sequence_cost(memo, keypad, path, depth - 1))
+            .min()
+            .unwrap()
+    })
 }
 
-fn get_key_position(keypad: &Array2<char>, key: char) -> (usize, usize) {
-    keypad
-        .indexed_iter()
-        .find(|(_position, &ch)| ch == key)
-        .unwrap()
-        .0
+/// The number of presses it takes to type `keys` on a directional keypad that is `depth`
+/// keypads away from the human, with every arm starting on `A`.
+fn sequence_cost(memo: &mut CostMemo, keypad: &Keypad, keys: &[char], depth: usize) -> u64 {
+    let mut from = 'A';
+    keys.iter()
+        .map(|&to| {
+            let cost = cost(memo, keypad, from, to, depth);
+            from = to;
+            cost
+        })
+        .sum()
 }
 
-fn map_keypad(keypad: &Array2<char>, mut code: String) -> String {
-    code.insert(0, 'A');
+/// The number of presses it takes to type `code` on the numeric keypad through `robots`
+/// directional keypads operated by robots.
+fn code_cost(memo: &mut CostMemo, code: &str, robots: usize) -> u64 {
+    let (numeric, directional) = (Keypad::numeric(), Keypad::directional());
+    let mut from = 'A';
     code.chars()
-        .tuple_windows()
-        .flat_map(|(c1, c2)| {
-            let p1 = get_key_position(keypad, c1);
-            let p2 = get_key_position(keypad, c2);
-            move_to_key(p1, p2, keypad)
+        .map(|to| {
+            let cost = numeric
+                .paths(from, to)
+                .iter()
+                .map(|path| sequence_cost(memo, &directional, path, robots))
+                .min()
+                .unwrap();
+            from = to;
+            cost
         })
-        .collect()
+        .sum()
 }
 
-fn parse_input_code(input_code: String) -> u32 {
-    input_code.strip_suffix("A").unwrap().parse().unwrap()
+fn total_complexity(input: &str, robots: usize) -> u64 {
+    let mut memo = Memo::new();
+    parse_input(input)
+        .into_iter()
+        .map(|(code, value)| code_cost(&mut memo, code, robots) * value)
+        .sum()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let codes: Vec<String> = input.lines().map(|s| s.to_string()).collect();
-
-    let numpad = num_pad();
-    let directional_pad = directional_pad();
-
-    let mut directional_codes = Vec::new();
-
-    for code in codes.iter() {
-        let code1 = map_keypad(&numpad, code.clone());
-        let code2 = map_keypad(&directional_pad, code1);
-        let code3 = map_keypad(&directional_pad, code2);
-        directional_codes.push(code3);
-    }
-    dbg!(&directional_codes);
-    let total_complexity = codes
-        .iter()
-        .zip(directional_codes)
-        .map(|(input_code, output_code)| {
-            parse_input_code(input_code.clone()) * output_code.len() as u32
-        })
-        .sum();
-    Some(total_complexity)
+pub fn part_one(input: &str) -> Option<u64> {
+    Some(total_complexity(input, 2))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let codes: Vec<String> = input.lines().map(|s| s.to_string()).collect();
-
-    let mut directional_codes = Vec::new();
-    let numpad = num_pad();
-    let directional_pad = directional_pad();
-
-    for code in codes.iter() {
-        directional_codes.push(map_keypad(&numpad, code.clone()));
-    }
-
-    for code in directional_codes.iter_mut() {
-        for i in 0..25 {
-            let new_code = map_keypad(&directional_pad, code.clone());
-            code.clear();
-            code.push_str(&new_code);
-            dbg!(i);
-        }
-    }
+pub fn part_two(input: &str) -> Option<u64> {
+    Some(total_complexity(input, 25))
+}
 
-    let total_complexity = codes
-        .iter()
-        .zip(directional_codes)
-        .map(|(input_code, output_code)| {
-            parse_input_code(input_code.clone()) * output_code.len() as u32
+/// The codes and their numeric parts.
+fn parse_input(input: &str) -> Vec<(&str, u64)> {
+    let numeric = Keypad::numeric();
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|code| {
+            if let Some(key) = code.chars().find(|&key| numeric.position(key).is_none()) {
+                AocError::at(code, format!("{key:?} is not a key of the numeric keypad")).raise();
+            }
+            let digits = code
+                .strip_suffix('A')
+                .unwrap_or_else(|| AocError::at(code, "a code must end with 'A'").raise());
+            (code, parse_at(digits).or_raise())
         })
-        .sum();
-    Some(total_complexity)
+        .collect()
 }
 
 #[cfg(test)]
@@ -199,6 +180,14 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(126384));
+        assert_eq!(result, Some(154115708116294));
+    }
+
+    #[test]
+    fn test_code_cost() {
+        let mut memo = Memo::new();
+        assert_eq!(code_cost(&mut memo, "029A", 0), "<A^A>^^AvvvA".len() as u64);
+        assert_eq!(code_cost(&mut memo, "029A", 1), 28);
+        assert_eq!(code_cost(&mut memo, "029A", 2), 68);
     }
 }
@@ -1,5 +1,4 @@
-use itertools::Itertools;
-use std::collections::HashMap;
+use advent_of_code::error::{parse_at, OrRaise};
 
 advent_of_code::solution!(22);
 
@@ -14,13 +13,18 @@ const fn evolve(secret: u64) -> u64 {
     result
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let secret_numbers: Vec<u64> = input
+/// Every price change lies in `-9..=9`, so a sequence of four is a 4-digit base-19 number.
+const SEQUENCES: usize = 19 * 19 * 19 * 19;
+
+fn parse_input(input: &str) -> Vec<u64> {
+    input
         .lines()
-        .map(|line| line.trim().parse().unwrap())
-        .collect();
+        .map(|line| parse_at(line.trim()).or_raise())
+        .collect()
+}
 
-    let total: u64 = secret_numbers
+pub fn part_one(input: &str) -> Option<u64> {
+    let total: u64 = parse_input(input)
         .into_iter()
         .map(|mut secret| {
             for _ in 0..2000 {
@@ -34,38 +38,55 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let initial_numbers: Vec<u64> = input
-        .lines()
-        .map(|line| line.trim().parse().unwrap())
-        .collect();
+    total_bananas(&parse_input(input)).into_iter().max()
+}
 
-    let secret_numbers_per_seller = initial_numbers.into_iter().map(|initial_number| {
-        (0..2000).scan(initial_number, |current, _i| {
-            *current = evolve(*current);
-            Some(*current)
-        })
-    });
-
-    let sellers_sequences = secret_numbers_per_seller.flat_map(|seller| {
-        seller
-            .map(|n| (n % 10) as i8)
-            .tuple_windows()
-            .map(|(a, b)| (b, b - a))
-            .tuple_windows::<(_, _, _, _)>()
-            .map(|(t1, t2, t3, t4)| ((t1.1, t2.1, t3.1, t4.1), t4.0))
-            .unique_by(|(sequence, _)| *sequence)
-    });
-
-    let mut sum_per_sequence = HashMap::new();
-
-    for (sequence, price) in sellers_sequences {
-        sum_per_sequence
-            .entry(sequence)
-            .and_modify(|subtotal| *subtotal += price as u32)
-            .or_insert(price as u32);
+/// The bananas that every sequence of price changes would buy from the buyers with `secrets`.
+fn bananas(secrets: &[u64]) -> Vec<u32> {
+    let mut bananas = vec![0; SEQUENCES];
+    // `seen[sequence]` is the number of the last buyer that has shown the sequence, which saves
+    // clearing the table for every buyer.
+    let mut seen = vec![0; SEQUENCES];
+
+    for (buyer, &secret) in (1..).zip(secrets) {
+        let mut secret = secret;
+        let mut price = (secret % 10) as usize;
+        let mut sequence = 0;
+
+        for i in 0..2000 {
+            secret = evolve(secret);
+            let next_price = (secret % 10) as usize;
+            sequence = (sequence * 19 + next_price + 9 - price) % SEQUENCES;
+            price = next_price;
+
+            // only the first occurrence of a sequence sells.
+            if i >= 3 && seen[sequence] != buyer {
+                seen[sequence] = buyer;
+                bananas[sequence] += price as u32;
+            }
+        }
     }
+    bananas
+}
 
-    sum_per_sequence.into_values().max()
+#[cfg(not(feature = "parallel"))]
+fn total_bananas(secrets: &[u64]) -> Vec<u32> {
+    bananas(secrets)
+}
+
+/// Splits the buyers into chunks with a table each and adds the tables up. Integer addition
+/// does not depend on the order, so the result is the same as on a single thread.
+#[cfg(feature = "parallel")]
+fn total_bananas(secrets: &[u64]) -> Vec<u32> {
+    use rayon::prelude::*;
+
+    secrets.par_chunks(256).map(bananas).reduce(
+        || vec![0; SEQUENCES],
+        |mut total, bananas| {
+            total.iter_mut().zip(bananas).for_each(|(t, b)| *t += b);
+            total
+        },
+    )
 }
 
 #[cfg(test)]
@@ -88,4 +109,13 @@ mod tests {
         );
         assert_eq!(result, Some(23));
     }
+
+    #[test]
+    fn test_bananas() {
+        // the buyer with the secret 123 sells 6 bananas for the changes -1,-1,0,2.
+        let sequence = [8, 8, 9, 11]
+            .iter()
+            .fold(0, |sequence, change| sequence * 19 + change);
+        assert_eq!(bananas(&[123])[sequence], 6);
+    }
 }
@@ -0,0 +1,395 @@
+//! Bit-packed sets for hot loops, as a faster alternative to `HashSet`.
+//!
+//! A [`DenseBitSet`] holds small integers such as node ids, a [`BitGrid`] holds positions on a
+//! map. Both use one bit per possible element, so set operations work on 64 elements at once.
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+use crate::geometry::{Index, Location};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of integers `0..n`, which grows as larger integers are inserted.
+#[derive(Clone, Default)]
+pub struct DenseBitSet {
+    words: Vec<u64>,
+}
+
+impl DenseBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty set that can hold `0..capacity` without growing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(BITS)],
+        }
+    }
+
+    /// Adds `value` to the set and returns whether it was newly inserted.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / BITS, 1 << (value % BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let is_new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        is_new
+    }
+
+    /// Removes `value` from the set and returns whether it was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / BITS, 1 << (value % BITS));
+        match self.words.get_mut(word) {
+            Some(bits) if *bits & bit != 0 => {
+                *bits &= !bit;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / BITS)
+            .is_some_and(|bits| bits & (1 << (value % BITS)) != 0)
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&bits| bits == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Iterates over the elements in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &bits)| {
+            let mut bits = bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(index * BITS + bit)
+            })
+        })
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (bits, other) in self.words.iter_mut().zip(&other.words) {
+            *bits |= other;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        for (index, bits) in self.words.iter_mut().enumerate() {
+            *bits &= other.words.get(index).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        for (bits, other) in self.words.iter_mut().zip(&other.words) {
+            *bits &= !other;
+        }
+    }
+
+    /// The size of the intersection with `other`, without building it.
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .enumerate()
+            .all(|(index, bits)| bits & !other.words.get(index).copied().unwrap_or(0) == 0)
+    }
+}
+
+/// Sets are equal if they have the same elements, regardless of their capacity.
+impl PartialEq for DenseBitSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_subset(other) && other.is_subset(self)
+    }
+}
+
+impl Eq for DenseBitSet {}
+
+impl Debug for DenseBitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for DenseBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for DenseBitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.insert(value);
+        });
+    }
+}
+
+impl BitOr for &DenseBitSet {
+    type Output = DenseBitSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut union = self.clone();
+        union.union_with(rhs);
+        union
+    }
+}
+
+impl BitAnd for &DenseBitSet {
+    type Output = DenseBitSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut intersection = self.clone();
+        intersection.intersect_with(rhs);
+        intersection
+    }
+}
+
+impl Sub for &DenseBitSet {
+    type Output = DenseBitSet;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut difference = self.clone();
+        difference.difference_with(rhs);
+        difference
+    }
+}
+
+/// A set of positions on a map of a fixed size.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    bits: DenseBitSet,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: DenseBitSet::with_capacity(width * height),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `(height, width)`, i.e. the shape of the equivalent `Array2`.
+    pub fn dim(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    fn bit(&self, at: impl Location) -> Option<usize> {
+        let (row, column) = at.locate(self.dim())?;
+        Some(row * self.width + column)
+    }
+
+    /// Adds `at` to the set and returns whether it was newly inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` lies outside of the map.
+    pub fn insert(&mut self, at: impl Location + Debug + Copy) -> bool {
+        let bit = self
+            .bit(at)
+            .unwrap_or_else(|| panic!("{at:?} is out of bounds"));
+        self.bits.insert(bit)
+    }
+
+    pub fn remove(&mut self, at: impl Location) -> bool {
+        self.bit(at).is_some_and(|bit| self.bits.remove(bit))
+    }
+
+    /// Whether `at` is in the set. Locations outside of the map never are.
+    pub fn contains(&self, at: impl Location) -> bool {
+        self.bit(at).is_some_and(|bit| self.bits.contains(bit))
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Iterates over the positions in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Index> + '_ {
+        self.bits
+            .iter()
+            .map(|bit| (bit / self.width, bit % self.width))
+    }
+
+    /// The positions as a set of indices `row * width + column`.
+    pub fn as_bitset(&self) -> &DenseBitSet {
+        &self.bits
+    }
+
+    /// Adds all positions of `other`, which must have the same size.
+    pub fn union_with(&mut self, other: &Self) {
+        assert_eq!(self.dim(), other.dim(), "the grids differ in size");
+        self.bits.union_with(&other.bits);
+    }
+
+    /// Keeps the positions that are also in `other`, which must have the same size.
+    pub fn intersect_with(&mut self, other: &Self) {
+        assert_eq!(self.dim(), other.dim(), "the grids differ in size");
+        self.bits.intersect_with(&other.bits);
+    }
+}
+
+impl<L: Location + Debug + Copy> Extend<L> for BitGrid {
+    fn extend<I: IntoIterator<Item = L>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|at| {
+            self.insert(at);
+        });
+    }
+}
+
+impl Debug for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    use super::{BitGrid, DenseBitSet};
+    use crate::geometry::Point;
+
+    #[test]
+    fn inserts_and_removes() {
+        let mut set = DenseBitSet::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(130));
+        assert!(set.contains(130));
+        assert!(!set.contains(1000));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![130]);
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a: DenseBitSet = [1, 2, 3, 100].into_iter().collect();
+        let b: DenseBitSet = [2, 3, 4].into_iter().collect();
+
+        assert_eq!(&a | &b, [1, 2, 3, 4, 100].into_iter().collect());
+        assert_eq!(&a & &b, [2, 3].into_iter().collect());
+        assert_eq!(&a - &b, [1, 100].into_iter().collect());
+        assert_eq!(a.intersection_len(&b), 2);
+        assert!((&a & &b).is_subset(&a));
+        assert!(!a.is_disjoint(&b));
+    }
+
+    #[test]
+    fn ignores_capacity_in_comparisons() {
+        let mut a = DenseBitSet::with_capacity(1000);
+        a.insert(5);
+        let b: DenseBitSet = [5].into_iter().collect();
+        assert_eq!(a, b);
+        assert_eq!(format!("{a:?}"), "{5}");
+    }
+
+    #[test]
+    fn tracks_positions() {
+        let mut grid = BitGrid::new(3, 2);
+        assert!(grid.insert((1, 2)));
+        assert!(grid.insert(Point::new(0, 1)));
+        assert!(!grid.insert((1, 0)));
+        grid.extend([(1, 2), (0, 0)]);
+        assert!(grid.remove((0, 0)));
+
+        assert!(grid.contains(Point::new(2, 1)));
+        assert!(!grid.contains(Point::new(-1, 0)));
+        assert!(!grid.contains((2, 0)));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(1, 0), (1, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn rejects_positions_outside_the_map() {
+        BitGrid::new(3, 2).insert((0, 3));
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_hash_sets(
+            a in proptest::collection::vec(0usize..300, 0..50),
+            b in proptest::collection::vec(0usize..300, 0..50),
+        ) {
+            let (set_a, set_b): (DenseBitSet, DenseBitSet) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+            let (hash_a, hash_b): (HashSet<usize>, HashSet<usize>) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+
+            let sorted = |set: HashSet<usize>| {
+                let mut values: Vec<_> = set.into_iter().collect();
+                values.sort_unstable();
+                values
+            };
+            prop_assert_eq!((&set_a | &set_b).iter().collect::<Vec<_>>(), sorted(&hash_a | &hash_b));
+            prop_assert_eq!((&set_a & &set_b).iter().collect::<Vec<_>>(), sorted(&hash_a & &hash_b));
+            prop_assert_eq!((&set_a - &set_b).iter().collect::<Vec<_>>(), sorted(&hash_a - &hash_b));
+            prop_assert_eq!(set_a.intersection_len(&set_b), hash_a.intersection(&hash_b).count());
+            prop_assert_eq!(set_a.is_disjoint(&set_b), hash_a.is_disjoint(&hash_b));
+            prop_assert_eq!(set_a.is_subset(&set_b), hash_a.is_subset(&hash_b));
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Cycle detection for simulations that eventually repeat.
+//!
+//! A simulation is described by its initial state and a `step` function. [`brent`] and [`floyd`]
+//! find the cycle in constant memory, [`detect`] remembers every state and can therefore jump
+//! to the state after any number of steps with [`state_after`].
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+/// A cycle of a sequence of states: the state at `start + length` equals the state at `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The index of the first state that is part of the cycle.
+    pub start: usize,
+    /// The number of states in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` to the equivalent step within the first pass of the cycle.
+    pub fn normalize(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycle of length {} starting at step {}",
+            self.length, self.start
+        )
+    }
+}
+
+/// Brent's algorithm. Usually needs fewer steps than [`floyd`].
+pub fn brent<S: Clone + Eq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle {
+        start: find_start(initial, &step, length),
+        length,
+    }
+}
+
+/// Floyd's tortoise and hare algorithm.
+pub fn floyd<S: Clone + Eq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let start = {
+        let mut tortoise = initial.clone();
+        let mut start = 0;
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            start += 1;
+        }
+        start
+    };
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Finds the first state of a cycle of known `length`, by running two states `length` steps apart.
+fn find_start<S: Clone + Eq>(initial: S, step: &impl Fn(&S) -> S, length: usize) -> usize {
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    start
+}
+
+/// Runs the simulation until a state repeats and returns the cycle together with all states
+/// visited before, i.e. `states[i]` is the state after `i` steps.
+pub fn detect<S: Clone + Eq + Hash>(initial: S, step: impl Fn(&S) -> S) -> (Cycle, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return (cycle, states);
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Returns the state after `n` steps, skipping repetitions once a cycle is found.
+pub fn state_after<S: Clone + Eq + Hash>(initial: S, step: impl Fn(&S) -> S, n: usize) -> S {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    while states.len() < n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return states.swap_remove(cycle.normalize(n));
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, detect, floyd, state_after, Cycle};
+
+    /// `0, 1, 2, 3, 4, 5, 3, 4, 5, ...`
+    fn step(n: &u32) -> u32 {
+        if *n == 5 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    const CYCLE: Cycle = Cycle {
+        start: 3,
+        length: 3,
+    };
+
+    #[test]
+    fn detects_cycles() {
+        assert_eq!(brent(0, step), CYCLE);
+        assert_eq!(floyd(0, step), CYCLE);
+
+        let (cycle, states) = detect(0, step);
+        assert_eq!(cycle, CYCLE);
+        assert_eq!(states, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn detects_fixed_points() {
+        let expected = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(brent(7, |n| *n), expected);
+        assert_eq!(floyd(7, |n| *n), expected);
+    }
+
+    #[test]
+    fn skips_repetitions() {
+        assert_eq!(state_after(0, step, 0), 0);
+        assert_eq!(state_after(0, step, 2), 2);
+        assert_eq!(state_after(0, step, 6), 3);
+        assert_eq!(state_after(0, step, 1_000_000_000), 4);
+    }
+
+    #[test]
+    fn agrees_with_simulation() {
+        let lcg = |n: &u64| (n * 37 + 11) % 1000;
+        let cycle = brent(5, lcg);
+        assert_eq!(floyd(5, lcg), cycle);
+
+        let mut state = 5;
+        for _ in 0..cycle.normalize(12345) {
+            state = lcg(&state);
+        }
+        assert_eq!(state_after(5, lcg, 12345), state);
+        assert_eq!(
+            cycle.to_string(),
+            format!(
+                "cycle of length {} starting at step {}",
+                cycle.length, cycle.start
+            )
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! A disjoint-set forest for incremental connectivity.
+//!
+//! Elements are the integers `0..n`, e.g. the indices of the tiles of a map. Sets can only be
+//! merged, never split, so questions like "when do these two tiles become disconnected?" are
+//! answered by replaying the events backwards: start from the final state and undo them one by
+//! one until the tiles connect, see [`first_separating`].
+use ndarray::Array2;
+
+use crate::geometry::{Direction, Index};
+
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    /// The parent of every element; roots are their own parent.
+    parents: Vec<usize>,
+    /// The size of the set of every root.
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// The representative of the set of `element`.
+    ///
+    /// Halves the path to the root on the way, so that later lookups are nearly constant.
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            let grandparent = self.parents[self.parents[element]];
+            self.parents[element] = grandparent;
+            element = grandparent;
+        }
+        element
+    }
+
+    /// Merges the sets of `a` and `b` and returns whether they were disjoint.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // hang the smaller tree below the larger one to keep the trees shallow.
+        let (root, child) = if self.sizes[a] < self.sizes[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.sets -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set of `element`.
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+}
+
+/// The index of the first of `blocks` after which there is no path from `from` to `to` through
+/// the free tiles of a map of `shape`, or `None` if there is one with all blocks in place.
+/// Blocks may fall on the same tile more than once.
+///
+/// Places all blocks, then removes them again in reverse order and merges every freed tile with
+/// its free neighbors, until `from` and `to` are in the same set.
+pub fn first_separating(
+    shape: (usize, usize),
+    blocks: &[Index],
+    from: Index,
+    to: Index,
+) -> Option<usize> {
+    let index = |(row, column): Index| row * shape.1 + column;
+    let (from, to) = (index(from), index(to));
+
+    // a tile becomes free again once the first block that fell on it is removed.
+    let mut first_block = Array2::from_elem(shape, None);
+    for (i, &position) in blocks.iter().enumerate().rev() {
+        first_block[position] = Some(i);
+    }
+
+    let initially_free = first_block
+        .indexed_iter()
+        .filter(|(_, block)| block.is_none())
+        .map(|(position, _)| (None, position));
+    let removed = blocks
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|&(i, &position)| first_block[position] == Some(i))
+        .map(|(i, &position)| (Some(i), position));
+
+    let mut regions = DisjointSet::new(shape.0 * shape.1);
+    let mut free = Array2::from_elem(shape, false);
+    for (block, position) in initially_free.chain(removed) {
+        free[position] = true;
+        for next in Direction::ALL.map(|direction| direction.step_index(position, shape)) {
+            if let Some(next) = next.filter(|&next| free[next]) {
+                regions.union(index(position), index(next));
+            }
+        }
+        // if the tiles connect before any block is removed, no block separates them.
+        if regions.connected(from, to) {
+            return block;
+        }
+    }
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{first_separating, DisjointSet};
+
+    #[test]
+    fn merges_sets() {
+        let mut sets = DisjointSet::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_size(5), 1);
+        assert_eq!(sets.set_count(), 3);
+        assert_eq!(sets.len(), 6);
+    }
+
+    #[test]
+    fn finds_separating_blocks() {
+        // the second block closes the wall in the middle column, the duplicate reopens nothing.
+        let blocks = [(0, 1), (1, 1), (0, 1), (2, 1), (1, 0)];
+        assert_eq!(first_separating((3, 3), &blocks, (0, 0), (2, 2)), Some(3));
+        assert_eq!(first_separating((3, 3), &blocks[..3], (0, 0), (2, 2)), None);
+        assert_eq!(first_separating((3, 3), &[(2, 2)], (0, 0), (2, 2)), Some(0));
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_labels(unions in proptest::collection::vec((0usize..20, 0usize..20), 0..30)) {
+            let mut sets = DisjointSet::new(20);
+            let mut labels: Vec<usize> = (0..20).collect();
+
+            for (a, b) in unions {
+                let (from, to) = (labels[a], labels[b]);
+                prop_assert_eq!(sets.union(a, b), from != to);
+                labels.iter_mut().filter(|label| **label == from).for_each(|label| *label = to);
+            }
+
+            for a in 0..20 {
+                for b in 0..20 {
+                    prop_assert_eq!(sets.connected(a, b), labels[a] == labels[b]);
+                }
+                let size = labels.iter().filter(|&&label| label == labels[a]).count();
+                prop_assert_eq!(sets.set_size(a), size);
+            }
+            labels.sort_unstable();
+            labels.dedup();
+            prop_assert_eq!(sets.set_count(), labels.len());
+        }
+    }
+}
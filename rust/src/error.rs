@@ -0,0 +1,297 @@
+//! Errors that point at the part of the puzzle input they were caused by.
+//!
+//! Create an [`AocError`] with [`AocError::at`] from the slice of the input that could not be
+//! parsed. As long as the slice borrows from the input, the error knows its line and column
+//! without the parser having to count them. Solutions that cannot continue call
+//! [`AocError::raise`], and the runner prints the error below an excerpt of the input:
+//!
+//! ```text
+//! error: invalid tile 'x'
+//!  --> line 3, column 4
+//!   |
+//! 3 | #..x.#
+//!   |    ^
+//! ```
+use std::{
+    cell::Cell,
+    fmt::{Debug, Display},
+    panic::{self, AssertUnwindSafe},
+    str::FromStr,
+    sync::Once,
+};
+
+use crate::parse::LineError;
+
+thread_local! {
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A location in the input. Lines and columns start at 1, columns count characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// The number of characters the span covers.
+    pub len: usize,
+}
+
+impl Span {
+    /// The span of `fragment` within `input`, if `fragment` is a slice of `input`.
+    pub fn of(input: &str, fragment: &str) -> Option<Self> {
+        let start = (fragment.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+        if start + fragment.len() > input.len() {
+            return None;
+        }
+
+        let before = &input[..start];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Some(Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            len: fragment.chars().count(),
+        })
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Unknown,
+    /// The address and length of a slice that is resolved against the input later on.
+    Fragment(usize, usize),
+    Span(Span),
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct AocError {
+    message: String,
+    location: Location,
+}
+
+impl AocError {
+    /// An error without a location in the input.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: Location::Unknown,
+        }
+    }
+
+    /// An error caused by `fragment`, which should be a slice of the puzzle input.
+    pub fn at(fragment: &str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: Location::Fragment(fragment.as_ptr() as usize, fragment.len()),
+        }
+    }
+
+    /// Sets the location of the error explicitly.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.location = Location::Span(span);
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The location of the error within `input`, if it is known.
+    pub fn span(&self, input: &str) -> Option<Span> {
+        match self.location {
+            Location::Unknown => None,
+            Location::Fragment(address, len) => {
+                let start = address.checked_sub(input.as_ptr() as usize)?;
+                let fragment = input.get(start..start.checked_add(len)?)?;
+                Span::of(input, fragment)
+            }
+            Location::Span(span) => Some(span),
+        }
+    }
+
+    /// Formats the error with the line of `input` it points at and a caret below the span.
+    pub fn render(&self, input: &str) -> String {
+        let mut output = format!("error: {}", self.message);
+        let Some(span) = self.span(input) else {
+            return output;
+        };
+        let Some(line) = input.lines().nth(span.line - 1) else {
+            return output;
+        };
+
+        let number = span.line.to_string();
+        let gutter = " ".repeat(number.len());
+        // spans over several lines are only underlined up to the end of the first one.
+        let rest = line.chars().count().saturating_sub(span.column - 1);
+        let carets = "^".repeat(span.len.min(rest).max(1));
+        output += &format!("\n{gutter}--> {span}\n{gutter} |\n{number} | {line}");
+        output += &format!("\n{gutter} | {}{carets}", " ".repeat(span.column - 1));
+        output
+    }
+
+    /// Aborts the solution with this error. Within the runner, it is reported with
+    /// [`AocError::render`]; elsewhere, e.g. in tests, it panics with the error message.
+    pub fn raise(self) -> ! {
+        if CAPTURING.with(Cell::get) {
+            panic::panic_any(self)
+        }
+        panic!("{self}")
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Location::Span(span) = self.location {
+            write!(f, " ({span})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints like `Display`, so that errors read well when a `Result` is unwrapped.
+impl Debug for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for AocError {}
+
+impl From<LineError> for AocError {
+    fn from(error: LineError) -> Self {
+        let span = Span {
+            line: error.line,
+            column: 1,
+            len: error.text.chars().count(),
+        };
+        Self::new(format!(
+            "could not parse {:?}: {}",
+            error.text, error.reason
+        ))
+        .with_span(span)
+    }
+}
+
+/// Unwraps a result, raising its error with [`AocError::raise`].
+pub trait OrRaise<T> {
+    fn or_raise(self) -> T;
+}
+
+impl<T, E: Into<AocError>> OrRaise<T> for Result<T, E> {
+    fn or_raise(self) -> T {
+        self.unwrap_or_else(|e| e.into().raise())
+    }
+}
+
+/// Parses `fragment` with `FromStr`, pointing at it if it fails.
+pub fn parse_at<T>(fragment: &str) -> Result<T, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fragment
+        .parse()
+        .map_err(|e| AocError::at(fragment, format!("could not parse {fragment:?}: {e}")))
+}
+
+/// Runs `f`, catching an [`AocError`] it raises. Other panics are passed on.
+pub fn capture<R>(f: impl FnOnce() -> R) -> Result<R, AocError> {
+    static SILENCE_HOOK: Once = Once::new();
+    SILENCE_HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().downcast_ref::<AocError>().is_none() {
+                default(info);
+            }
+        }));
+    });
+
+    let was_capturing = CAPTURING.with(|capturing| capturing.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CAPTURING.with(|capturing| capturing.set(was_capturing));
+
+    result.map_err(|payload| match payload.downcast::<AocError>() {
+        Ok(error) => *error,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{capture, parse_at, AocError, Span};
+
+    const INPUT: &str = "#....#\n#.@..#\n#..x.#\n";
+
+    #[test]
+    fn locates_fragments() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let span = Span::of(INPUT, &line[3..4]);
+        assert_eq!(
+            span,
+            Some(Span {
+                line: 3,
+                column: 4,
+                len: 1
+            })
+        );
+        assert_eq!(Span::of(INPUT, &String::from("x")), None);
+    }
+
+    #[test]
+    fn renders_excerpts() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let error = AocError::at(&line[3..4], "invalid tile 'x'");
+
+        let expected = "error: invalid tile 'x'\n --> line 3, column 4\n  |\n3 | #..x.#\n  |    ^";
+        assert_eq!(error.render(INPUT), expected);
+        assert_eq!(error.to_string(), "invalid tile 'x'");
+        assert_eq!(error.render("unrelated"), "error: invalid tile 'x'");
+    }
+
+    #[test]
+    fn underlines_the_first_line_of_a_span() {
+        let error = AocError::at(&INPUT[9..16], "no wall");
+        let expected = "error: no wall\n --> line 2, column 3\n  |\n2 | #.@..#\n  |   ^^^^";
+        assert_eq!(error.render(INPUT), expected);
+    }
+
+    #[test]
+    fn parses_fragments() {
+        let input = "12,x4";
+        assert_eq!(parse_at::<u8>(&input[..2]), Ok(12));
+
+        let error = parse_at::<u8>(&input[3..]).unwrap_err();
+        assert_eq!(error.span(input).map(|span| span.column), Some(4));
+    }
+
+    #[test]
+    fn captures_raised_errors() {
+        let error = AocError::new("no start").with_span(Span {
+            line: 2,
+            column: 1,
+            len: 3,
+        });
+        let raised = error.clone();
+        assert_eq!(capture(|| raised.raise()), Err::<(), _>(error));
+        assert_eq!(capture(|| 42), Ok(42));
+    }
+
+    #[test]
+    #[should_panic(expected = "no start (line 2, column 1)")]
+    fn panics_outside_of_the_runner() {
+        AocError::new("no start")
+            .with_span(Span {
+                line: 2,
+                column: 1,
+                len: 3,
+            })
+            .raise();
+    }
+}
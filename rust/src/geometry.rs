@@ -0,0 +1,266 @@
+//! Points and directions on a 2D map.
+//!
+//! `x` grows to the right and `y` grows downwards, so a point `(x, y)` corresponds to the
+//! `(row, column)` index `(y, x)` of an `ndarray::Array2` or a [`Grid`](crate::grid::Grid).
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// An `(row, column)` index into a map, as used by `ndarray` and [`crate::grid`].
+pub type Index = (usize, usize);
+
+/// A location on a map, either an `(row, column)` index or a [`Point`].
+pub trait Location {
+    /// The index of the location, if it lies within a map of shape `(height, width)`.
+    fn locate(self, shape: (usize, usize)) -> Option<Index>;
+}
+
+impl Location for Index {
+    fn locate(self, (height, width): (usize, usize)) -> Option<Index> {
+        (self.0 < height && self.1 < width).then_some(self)
+    }
+}
+
+impl Location for Point {
+    fn locate(self, shape: (usize, usize)) -> Option<Index> {
+        self.to_index(shape)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Creates a point from an `(row, column)` index.
+    pub fn from_index((row, column): Index) -> Self {
+        Self::new(column as i32, row as i32)
+    }
+
+    /// Converts the point to an `(row, column)` index, if it lies within a map of shape `(height, width)`.
+    pub fn to_index(self, (height, width): (usize, usize)) -> Option<Index> {
+        let row = usize::try_from(self.y).ok()?;
+        let column = usize::try_from(self.x).ok()?;
+        (row < height && column < width).then_some((row, column))
+    }
+
+    pub fn manhattan(self, other: Self) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Wraps the point around the edges of a map with the given size.
+    pub fn rem_euclid(self, width: i32, height: i32) -> Self {
+        Self::new(self.x.rem_euclid(width), self.y.rem_euclid(height))
+    }
+
+    /// The point one step towards `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// Iterates over the orthogonal neighbors of the point.
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting north.
+    pub const ALL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    /// The offset of a single step in this direction.
+    pub const fn offset(self) -> Point {
+        match self {
+            Self::North => Point::new(0, -1),
+            Self::East => Point::new(1, 0),
+            Self::South => Point::new(0, 1),
+            Self::West => Point::new(-1, 0),
+        }
+    }
+
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// The dot product of both offsets: `1` for the same direction, `0` for a turn
+    /// and `-1` for the opposite direction.
+    pub const fn dot(self, other: Self) -> i32 {
+        let (a, b) = (self.offset(), other.offset());
+        a.x * b.x + a.y * b.y
+    }
+
+    /// Steps from an `(row, column)` index, if the result lies within a map of shape `(height, width)`.
+    pub fn step_index(self, index: Index, dim: (usize, usize)) -> Option<Index> {
+        Point::from_index(index).step(self).to_index(dim)
+    }
+}
+
+/// Parses the arrows used for movement instructions, e.g. `^`, `>`, `v` and `<`.
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Self::North),
+            '>' => Ok(Self::East),
+            'v' => Ok(Self::South),
+            '<' => Ok(Self::West),
+            c => Err(c),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point};
+
+    #[test]
+    fn adds_points() {
+        let mut point = Point::new(1, 2) + Point::new(3, -4);
+        assert_eq!(point, Point::new(4, -2));
+        point -= Point::new(4, 0);
+        assert_eq!(point, Point::new(0, -2));
+        assert_eq!(-point * 3, Point::new(0, 6));
+    }
+
+    #[test]
+    fn measures_distances() {
+        assert_eq!(Point::new(1, 1).manhattan(Point::new(-2, 5)), 7);
+        assert_eq!(Point::new(-1, 7).rem_euclid(5, 5), Point::new(4, 2));
+    }
+
+    #[test]
+    fn converts_indices() {
+        let point = Point::from_index((2, 3));
+        assert_eq!(point, Point::new(3, 2));
+        assert_eq!(point.to_index((3, 4)), Some((2, 3)));
+        assert_eq!(point.to_index((2, 4)), None);
+        assert_eq!(Point::new(-1, 0).to_index((3, 3)), None);
+    }
+
+    #[test]
+    fn turns_directions() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.dot(direction), 1);
+            assert_eq!(direction.dot(direction.turn_left()), 0);
+            assert_eq!(direction.dot(direction.opposite()), -1);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+    }
+
+    #[test]
+    fn steps_within_bounds() {
+        assert_eq!(Direction::North.step_index((0, 0), (2, 2)), None);
+        assert_eq!(Direction::East.step_index((0, 0), (2, 2)), Some((0, 1)));
+        assert_eq!(Direction::South.step_index((1, 0), (2, 2)), None);
+        assert_eq!(Point::new(1, 1).neighbors().count(), 4);
+    }
+
+    #[test]
+    fn parses_arrows() {
+        assert_eq!(Direction::try_from('<'), Ok(Direction::West));
+        assert_eq!(Direction::try_from('x'), Err('x'));
+    }
+}
@@ -0,0 +1,308 @@
+//! Algorithms on undirected `petgraph` graphs that petgraph does not provide itself.
+//!
+//! Build the graph with [`undirected_from_edges`], which interns the node names so that they
+//! can be looked up again, then search it for [`triangles`], [`maximal_cliques`] or
+//! [`connected_components`].
+use std::{collections::HashMap, hash::Hash};
+
+use petgraph::{
+    graph::{NodeIndex, UnGraph},
+    visit::Dfs,
+};
+
+use crate::bitset::DenseBitSet;
+
+/// Assigns dense ids `0, 1, 2, ...` to keys in the order they are first seen.
+#[derive(Debug, Clone)]
+pub struct Interner<K> {
+    ids: HashMap<K, usize>,
+    keys: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash> Interner<K> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Returns the id of `key`, assigning the next free id if it has none yet.
+    pub fn intern(&mut self, key: K) -> usize {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let id = self.keys.len();
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        id
+    }
+
+    pub fn get(&self, key: &K) -> Option<usize> {
+        self.ids.get(key).copied()
+    }
+
+    /// The key with the id `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no key has the id `id`.
+    pub fn resolve(&self, id: usize) -> &K {
+        &self.keys[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<K: Clone + Eq + Hash> Default for Interner<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds an undirected graph whose nodes are the keys of `edges`.
+/// The node index of every key is its id in the returned interner.
+pub fn undirected_from_edges<K: Clone + Eq + Hash>(
+    edges: impl IntoIterator<Item = (K, K)>,
+) -> (UnGraph<K, ()>, Interner<K>) {
+    let mut graph = UnGraph::new_undirected();
+    let mut interner = Interner::new();
+
+    let mut node = |graph: &mut UnGraph<K, ()>, key: K| {
+        let id = interner.intern(key.clone());
+        if id == graph.node_count() {
+            graph.add_node(key);
+        }
+        NodeIndex::new(id)
+    };
+    for (a, b) in edges {
+        let a = node(&mut graph, a);
+        let b = node(&mut graph, b);
+        graph.add_edge(a, b, ());
+    }
+
+    (graph, interner)
+}
+
+/// The indices of the neighbors of every node, without self loops.
+fn adjacency<N, E>(graph: &UnGraph<N, E>) -> Vec<DenseBitSet> {
+    graph
+        .node_indices()
+        .map(|node| {
+            let mut neighbors = DenseBitSet::with_capacity(graph.node_count());
+            neighbors.extend(
+                graph
+                    .neighbors(node)
+                    .filter(|&n| n != node)
+                    .map(NodeIndex::index),
+            );
+            neighbors
+        })
+        .collect()
+}
+
+/// All triangles of the graph, each with its nodes in ascending order.
+///
+/// Every edge is directed from the node of lower degree to the one of higher degree, so that
+/// each triangle is found exactly once in `O(m * sqrt(m))`.
+pub fn triangles<N, E>(graph: &UnGraph<N, E>) -> Vec<[NodeIndex; 3]> {
+    let neighbors = adjacency(graph);
+    let rank = |node: NodeIndex| (neighbors[node.index()].len(), node);
+    let forward: Vec<Vec<NodeIndex>> = graph
+        .node_indices()
+        .map(|node| {
+            neighbors[node.index()]
+                .iter()
+                .map(NodeIndex::new)
+                .filter(|&n| rank(n) > rank(node))
+                .collect()
+        })
+        .collect();
+
+    let mut triangles = Vec::new();
+    for a in graph.node_indices() {
+        for &b in &forward[a.index()] {
+            for &c in &forward[b.index()] {
+                if neighbors[a.index()].contains(c.index()) {
+                    let mut triangle = [a, b, c];
+                    triangle.sort_unstable();
+                    triangles.push(triangle);
+                }
+            }
+        }
+    }
+    triangles
+}
+
+/// All maximal cliques, found with the Bron–Kerbosch algorithm with pivoting.
+/// The nodes of each clique are in ascending order.
+pub fn maximal_cliques<N, E>(graph: &UnGraph<N, E>) -> Vec<Vec<NodeIndex>> {
+    if graph.node_count() == 0 {
+        return Vec::new();
+    }
+    let neighbors = adjacency(graph);
+    let mut cliques = Vec::new();
+    bron_kerbosch(
+        &neighbors,
+        &mut Vec::new(),
+        (0..graph.node_count()).collect(),
+        DenseBitSet::with_capacity(graph.node_count()),
+        &mut cliques,
+    );
+    cliques
+}
+
+/// One of the largest cliques, with its nodes in ascending order.
+pub fn maximum_clique<N, E>(graph: &UnGraph<N, E>) -> Vec<NodeIndex> {
+    maximal_cliques(graph)
+        .into_iter()
+        .max_by_key(Vec::len)
+        .unwrap_or_default()
+}
+
+/// Extends the clique `current` with nodes from `candidates`, skipping cliques that contain a
+/// node of `excluded` because they were already reported.
+fn bron_kerbosch(
+    neighbors: &[DenseBitSet],
+    current: &mut Vec<NodeIndex>,
+    mut candidates: DenseBitSet,
+    mut excluded: DenseBitSet,
+    cliques: &mut Vec<Vec<NodeIndex>>,
+) {
+    // the pivot with the most neighbors among the candidates leaves the fewest branches.
+    let Some(pivot) = (&candidates | &excluded)
+        .iter()
+        .max_by_key(|&node| candidates.intersection_len(&neighbors[node]))
+    else {
+        let mut clique = current.clone();
+        clique.sort_unstable();
+        cliques.push(clique);
+        return;
+    };
+
+    for node in (&candidates - &neighbors[pivot]).iter() {
+        let adjacent = &neighbors[node];
+        current.push(NodeIndex::new(node));
+        bron_kerbosch(
+            neighbors,
+            current,
+            &candidates & adjacent,
+            &excluded & adjacent,
+            cliques,
+        );
+        current.pop();
+
+        candidates.remove(node);
+        excluded.insert(node);
+    }
+}
+
+/// The connected components of the graph, each with its nodes in ascending order.
+pub fn connected_components<N, E>(graph: &UnGraph<N, E>) -> Vec<Vec<NodeIndex>> {
+    let mut seen = vec![false; graph.node_count()];
+    let mut components = Vec::new();
+
+    for start in graph.node_indices() {
+        if seen[start.index()] {
+            continue;
+        }
+        let mut component = Vec::new();
+        let mut dfs = Dfs::new(graph, start);
+        while let Some(node) = dfs.next(graph) {
+            seen[node.index()] = true;
+            component.push(node);
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+    components
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use petgraph::graph::NodeIndex;
+
+    use super::{
+        connected_components, maximal_cliques, maximum_clique, triangles, undirected_from_edges,
+        Interner,
+    };
+
+    fn nodes(indices: &[usize]) -> Vec<NodeIndex> {
+        indices.iter().copied().map(NodeIndex::new).collect()
+    }
+
+    #[test]
+    fn interns_keys() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern("kh"), 0);
+        assert_eq!(interner.intern("tc"), 1);
+        assert_eq!(interner.intern("kh"), 0);
+        assert_eq!(interner.get(&"tc"), Some(1));
+        assert_eq!(interner.get(&"qp"), None);
+        assert_eq!(interner.resolve(1), &"tc");
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn builds_graphs() {
+        let (graph, interner) = undirected_from_edges([("a", "b"), ("b", "c"), ("c", "a")]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        for id in 0..3 {
+            assert_eq!(graph[NodeIndex::new(id)], *interner.resolve(id));
+        }
+    }
+
+    #[test]
+    fn finds_triangles() {
+        // two triangles sharing the edge 1-2, and a pendant edge 3-4.
+        let (graph, _) = undirected_from_edges([(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (3, 4)]);
+        let mut found = triangles(&graph);
+        found.sort();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].to_vec(), nodes(&[0, 1, 2]));
+        assert_eq!(found[1].to_vec(), nodes(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn finds_cliques() {
+        // a 4-clique 0-1-2-3 plus a triangle 3-4-5.
+        let edges = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+            (3, 5),
+            (4, 5),
+        ];
+        let (graph, _) = undirected_from_edges(edges);
+
+        let mut cliques = maximal_cliques(&graph);
+        cliques.sort();
+        assert_eq!(cliques, vec![nodes(&[0, 1, 2, 3]), nodes(&[3, 4, 5])]);
+        assert_eq!(maximum_clique(&graph), nodes(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn finds_components() {
+        let (mut graph, _) = undirected_from_edges([(0, 1), (2, 3), (3, 4)]);
+        graph.add_node(5);
+
+        let components = connected_components(&graph);
+        assert_eq!(
+            components,
+            vec![nodes(&[0, 1]), nodes(&[2, 3, 4]), nodes(&[5])]
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! A rectangular grid of tiles, as used by most map-based puzzles.
+//!
+//! Positions are `(row, column)` tuples, which matches the indices of `ndarray::Array2`.
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use ndarray::Array2;
+
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// A grid of `width * height` tiles, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    tiles: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Parses a char map, converting every char with `tile`.
+    /// Leading and trailing whitespace of the input is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows of the map differ in length.
+    pub fn parse(input: &str, mut tile: impl FnMut(char) -> T) -> Self {
+        let mut width = 0;
+        let mut height = 0;
+        let mut tiles = Vec::with_capacity(input.len());
+
+        for line in input.trim().lines() {
+            let line = line.trim_end();
+            tiles.extend(line.chars().map(&mut tile));
+            height += 1;
+
+            if height == 1 {
+                width = tiles.len();
+            } else {
+                assert_eq!(
+                    tiles.len(),
+                    width * height,
+                    "row {height} has a different length"
+                );
+            }
+        }
+
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    /// Creates a grid from tiles stored row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of tiles does not match the dimensions.
+    pub fn from_vec(width: usize, height: usize, tiles: Vec<T>) -> Self {
+        assert_eq!(tiles.len(), width * height, "tiles do not match dimensions");
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `(height, width)`, i.e. the shape of the equivalent `Array2`.
+    pub fn dim(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    pub fn contains(&self, (row, column): Position) -> bool {
+        row < self.height && column < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.tiles[self.index_of(position)])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if !self.contains(position) {
+            return None;
+        }
+        let index = self.index_of(position);
+        Some(&mut self.tiles[index])
+    }
+
+    /// Returns the position `offset` away from `position`, if it is within the grid.
+    pub fn offset(&self, (row, column): Position, (dr, dc): (isize, isize)) -> Option<Position> {
+        let position = (row.checked_add_signed(dr)?, column.checked_add_signed(dc)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// Iterates over the orthogonal neighbors of `position` within the grid.
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_4
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    /// Iterates over the orthogonal and diagonal neighbors of `position` within the grid.
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_8
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    /// Iterates over all positions row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.tiles.len()).map(move |index| (index / width, index % width))
+    }
+
+    /// Iterates over all tiles together with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.tiles.iter())
+    }
+
+    /// Returns the position of the first tile matching `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.tiles
+            .iter()
+            .position(predicate)
+            .map(|index| self.position_of(index))
+    }
+
+    /// Iterates over the positions of all tiles matching `predicate`.
+    pub fn find_all<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Position> + 'a {
+        self.iter()
+            .filter(move |(_, tile)| predicate(tile))
+            .map(|(position, _)| position)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.tiles[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics for a chunk size of 0.
+        self.tiles.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, column: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(column < self.width, "column {column} is out of bounds");
+        self.tiles.iter().skip(column).step_by(self.width)
+    }
+
+    /// Converts every tile with `f`, keeping the dimensions.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            tiles: self.tiles.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, (row, column): Position) -> usize {
+        row * self.width + column
+    }
+
+    fn position_of(&self, index: usize) -> Position {
+        (index / self.width, index % self.width)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid filled with `tile`.
+    pub fn new(width: usize, height: usize, tile: T) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![tile; width * height],
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        let tiles = (0..self.width)
+            .flat_map(|column| self.column(column).cloned())
+            .collect();
+        Self::from_vec(self.height, self.width, tiles)
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate_clockwise(&self) -> Self {
+        let tiles = (0..self.width)
+            .flat_map(|column| self.column(column).rev().cloned())
+            .collect();
+        Self::from_vec(self.height, self.width, tiles)
+    }
+
+    /// Rotates the grid by 90 degrees counterclockwise.
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let tiles = (0..self.width)
+            .rev()
+            .flat_map(|column| self.column(column).cloned())
+            .collect();
+        Self::from_vec(self.height, self.width, tiles)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        assert!(self.contains(position), "{position:?} is out of bounds");
+        &self.tiles[self.index_of(position)]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        assert!(self.contains(position), "{position:?} is out of bounds");
+        let index = self.index_of(position);
+        &mut self.tiles[index]
+    }
+}
+
+/// Prints one row per line, e.g. to debug a map with tiles that implement `Display`.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{tile}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> From<Array2<T>> for Grid<T> {
+    fn from(array: Array2<T>) -> Self {
+        let (height, width) = array.dim();
+        // `iter` visits the elements in logical order, regardless of the memory layout.
+        Self::from_vec(width, height, array.iter().cloned().collect())
+    }
+}
+
+impl<T> From<Grid<T>> for Array2<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Array2::from_shape_vec(grid.dim(), grid.tiles).unwrap()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array2;
+
+    use super::Grid;
+
+    const MAP: &str = "#.S\n..#\n";
+
+    fn parse() -> Grid<char> {
+        Grid::parse(MAP, |c| c)
+    }
+
+    #[test]
+    fn parses_maps() {
+        let grid = parse();
+        assert_eq!(grid.dim(), (2, 3));
+        assert_eq!(grid[(0, 2)], 'S');
+        assert_eq!(grid.get((1, 2)), Some(&'#'));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_ragged_maps() {
+        Grid::parse("..\n...", |c| c);
+    }
+
+    #[test]
+    fn updates_tiles() {
+        let mut grid = parse();
+        *grid.get_mut((1, 0)).unwrap() = 'O';
+        grid[(1, 1)] = 'O';
+        assert_eq!(grid.row(1), &['O', 'O', '#']);
+        assert_eq!(grid.get_mut((5, 5)), None);
+    }
+
+    #[test]
+    fn iterates_neighbors() {
+        let grid = parse();
+        let mut neighbors: Vec<_> = grid.neighbors4((0, 0)).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors4((1, 1)).count(), 3);
+        assert_eq!(grid.neighbors8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbors8((1, 2)).count(), 3);
+    }
+
+    #[test]
+    fn finds_tiles() {
+        let grid = parse();
+        assert_eq!(grid.find(|&c| c == 'S'), Some((0, 2)));
+        assert_eq!(grid.find(|&c| c == 'E'), None);
+        assert_eq!(
+            grid.find_all(|&c| c == '#').collect::<Vec<_>>(),
+            vec![(0, 0), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn views_rows_and_columns() {
+        let grid = parse();
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(2).collect::<String>(), "S#");
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = parse();
+        assert_eq!(grid.transpose().to_string(), "#.\n..\nS#");
+        assert_eq!(grid.rotate_clockwise().to_string(), ".#\n..\n#S");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "S#\n..\n#.");
+        assert_eq!(
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise(),
+            grid.rotate_counterclockwise()
+        );
+    }
+
+    #[test]
+    fn displays_grids() {
+        assert_eq!(parse().to_string(), MAP.trim());
+        assert_eq!(parse().map(|&c| c == '#').row(0), &[true, false, false]);
+    }
+
+    #[test]
+    fn converts_arrays() {
+        let array: Array2<char> = parse().into();
+        assert_eq!(array[(0, 2)], 'S');
+        assert_eq!(Grid::from(array.clone()), parse());
+        assert_eq!(Grid::from(array.reversed_axes()), parse().transpose());
+    }
+}
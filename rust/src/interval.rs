@@ -0,0 +1,268 @@
+//! Allocation of contiguous extents, as in disk compaction puzzles.
+//!
+//! An [`Allocator`] tracks the free spans of a one-dimensional space, indexed both by position
+//! and by size. Allocation is first-fit: the free span with the lowest position that is large
+//! enough. Released extents are merged with adjacent free spans.
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A contiguous range of positions `start..start + len`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Extent {
+    pub start: u64,
+    pub len: u64,
+}
+
+impl Extent {
+    pub fn new(start: u64, len: u64) -> Self {
+        Self { start, len }
+    }
+
+    /// The first position after the extent.
+    pub fn end(&self) -> u64 {
+        self.start + self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The sum of all positions in the extent.
+    pub fn position_sum(&self) -> u64 {
+        if self.len == 0 {
+            return 0;
+        }
+        (self.start + self.end() - 1) * self.len / 2
+    }
+}
+
+/// The sum of `position * id` over every position of every file.
+pub fn checksum(files: impl IntoIterator<Item = (u64, Extent)>) -> u64 {
+    files
+        .into_iter()
+        .map(|(id, extent)| id * extent.position_sum())
+        .sum()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Allocator {
+    /// The length of each free span, keyed by its start.
+    by_start: BTreeMap<u64, u64>,
+    /// The starts of the free spans of each length.
+    by_len: BTreeMap<u64, BTreeSet<u64>>,
+}
+
+impl Allocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The free spans, ordered by position.
+    pub fn free_spans(&self) -> impl Iterator<Item = Extent> + '_ {
+        self.by_start
+            .iter()
+            .map(|(&start, &len)| Extent::new(start, len))
+    }
+
+    /// The total number of free positions.
+    pub fn free_len(&self) -> u64 {
+        self.by_start.values().sum()
+    }
+
+    /// The lowest free span that can hold `len` positions, without allocating it.
+    ///
+    /// Looks at the lowest span of every size of at least `len`, so it is logarithmic in the
+    /// number of spans and linear in the number of distinct sizes.
+    pub fn first_fit(&self, len: u64) -> Option<Extent> {
+        self.by_len
+            .range(len..)
+            .filter_map(|(&span_len, starts)| Some(Extent::new(*starts.first()?, span_len)))
+            .min_by_key(|span| span.start)
+    }
+
+    /// Allocates `len` positions at the start of the lowest free span that is large enough.
+    pub fn allocate(&mut self, len: u64) -> Option<Extent> {
+        self.allocate_before(len, u64::MAX)
+    }
+
+    /// Like [`Allocator::allocate`], but the allocated extent must end at or before `limit`.
+    pub fn allocate_before(&mut self, len: u64, limit: u64) -> Option<Extent> {
+        if len == 0 {
+            return None;
+        }
+        let span = self.first_fit(len)?;
+        if span.start + len > limit {
+            return None;
+        }
+        Some(self.take(span, len))
+    }
+
+    /// Allocates up to `max_len` positions at the start of the lowest free span, even if that span
+    /// is smaller, as long as the allocated extent ends at or before `limit`.
+    pub fn allocate_partial(&mut self, max_len: u64, limit: u64) -> Option<Extent> {
+        let span = self.free_spans().next()?;
+        let len = max_len.min(span.len).min(limit.saturating_sub(span.start));
+        if len == 0 {
+            return None;
+        }
+        Some(self.take(span, len))
+    }
+
+    /// Marks `extent` as free, merging it with the free spans around it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `extent` overlaps a span that is already free.
+    pub fn release(&mut self, extent: Extent) {
+        if extent.is_empty() {
+            return;
+        }
+        let mut merged = extent;
+
+        if let Some((&start, &len)) = self.by_start.range(..extent.end()).next_back() {
+            let previous = Extent::new(start, len);
+            assert!(
+                previous.end() <= extent.start,
+                "{extent:?} overlaps the free span {previous:?}"
+            );
+            if previous.end() == extent.start {
+                self.remove_span(previous);
+                merged = Extent::new(previous.start, previous.len + merged.len);
+            }
+        }
+        if let Some(&len) = self.by_start.get(&extent.end()) {
+            self.remove_span(Extent::new(extent.end(), len));
+            merged.len += len;
+        }
+
+        self.insert_span(merged);
+    }
+
+    /// Allocates the first `len` positions of the free `span`.
+    fn take(&mut self, span: Extent, len: u64) -> Extent {
+        self.remove_span(span);
+        self.insert_span(Extent::new(span.start + len, span.len - len));
+        Extent::new(span.start, len)
+    }
+
+    fn insert_span(&mut self, span: Extent) {
+        if span.is_empty() {
+            return;
+        }
+        self.by_start.insert(span.start, span.len);
+        self.by_len.entry(span.len).or_default().insert(span.start);
+    }
+
+    fn remove_span(&mut self, span: Extent) {
+        self.by_start.remove(&span.start);
+        if let Some(starts) = self.by_len.get_mut(&span.len) {
+            starts.remove(&span.start);
+            if starts.is_empty() {
+                self.by_len.remove(&span.len);
+            }
+        }
+    }
+}
+
+impl FromIterator<Extent> for Allocator {
+    /// Creates an allocator with the given free extents.
+    fn from_iter<I: IntoIterator<Item = Extent>>(iter: I) -> Self {
+        let mut allocator = Self::new();
+        iter.into_iter()
+            .for_each(|extent| allocator.release(extent));
+        allocator
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{checksum, Allocator, Extent};
+
+    #[test]
+    fn allocates_first_fit() {
+        let mut allocator: Allocator = [Extent::new(2, 3), Extent::new(8, 3), Extent::new(12, 1)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(allocator.allocate(1), Some(Extent::new(2, 1)));
+        assert_eq!(allocator.allocate(3), Some(Extent::new(8, 3)));
+        assert_eq!(allocator.allocate(2), Some(Extent::new(3, 2)));
+        assert_eq!(allocator.allocate(2), None);
+        assert_eq!(
+            allocator.free_spans().collect::<Vec<_>>(),
+            vec![Extent::new(12, 1)]
+        );
+    }
+
+    #[test]
+    fn respects_limits() {
+        let mut allocator: Allocator = [Extent::new(2, 3), Extent::new(8, 3)].into_iter().collect();
+
+        assert_eq!(allocator.allocate_before(3, 6), Some(Extent::new(2, 3)));
+        assert_eq!(allocator.allocate_before(3, 10), None);
+        assert_eq!(allocator.allocate_partial(5, 10), Some(Extent::new(8, 2)));
+        assert_eq!(allocator.allocate_partial(5, 10), None);
+        assert_eq!(allocator.free_len(), 1);
+    }
+
+    #[test]
+    fn merges_released_extents() {
+        let mut allocator: Allocator = [Extent::new(0, 2), Extent::new(4, 2)].into_iter().collect();
+        allocator.release(Extent::new(2, 2));
+
+        assert_eq!(
+            allocator.free_spans().collect::<Vec<_>>(),
+            vec![Extent::new(0, 6)]
+        );
+        assert_eq!(allocator.first_fit(6), Some(Extent::new(0, 6)));
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn rejects_double_release() {
+        let mut allocator: Allocator = [Extent::new(0, 4)].into_iter().collect();
+        allocator.release(Extent::new(3, 2));
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(Extent::new(3, 4).position_sum(), 3 + 4 + 5 + 6);
+        assert_eq!(Extent::new(3, 0).position_sum(), 0);
+        assert_eq!(
+            checksum([(0, Extent::new(0, 2)), (9, Extent::new(2, 2))]),
+            9 * 2 + 9 * 3
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_a_bitmap(
+            free in proptest::collection::vec(any::<bool>(), 1..64),
+            requests in proptest::collection::vec(1u64..6, 1..16),
+        ) {
+            let mut bitmap = free.clone();
+            let mut allocator: Allocator = free
+                .iter()
+                .enumerate()
+                .filter(|(_, &is_free)| is_free)
+                .map(|(position, _)| Extent::new(position as u64, 1))
+                .collect();
+
+            for len in requests {
+                let len = len as usize;
+                let expected = (0..bitmap.len().saturating_sub(len - 1))
+                    .find(|&start| bitmap[start..start + len].iter().all(|&is_free| is_free));
+                let allocated = allocator.allocate(len as u64);
+
+                prop_assert_eq!(allocated.map(|extent| extent.start as usize), expected);
+                if let Some(start) = expected {
+                    bitmap[start..start + len].fill(false);
+                }
+            }
+            prop_assert_eq!(allocator.free_len(), bitmap.iter().filter(|&&b| b).count() as u64);
+        }
+    }
+}
@@ -1,3 +1,17 @@
+pub mod bitset;
+pub mod cycle;
+pub mod disjoint_set;
+pub mod error;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod search;
 pub mod template;
+pub mod viz;
+pub mod vm;
 
 // Use this file to add helper functions and additional modules.
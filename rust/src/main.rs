@@ -1,26 +1,38 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, decrypt, download, encrypt, read, scaffold, solve, stars, time,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
+use advent_of_code::template::{commands::wait, Day, Event};
 #[cfg(feature = "today")]
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{
+        commands::scaffold::{ScaffoldOptions, TemplateOptions},
+        Day,
+    };
     use std::process;
 
     pub enum AppArguments {
         Download {
             day: Day,
         },
+        Encrypt {
+            day: Option<Day>,
+        },
+        Decrypt {
+            day: Option<Day>,
+        },
         Read {
             day: Day,
+            refresh: bool,
         },
         Scaffold {
             day: Day,
             download: bool,
-            overwrite: bool,
+            options: ScaffoldOptions,
         },
         Solve {
             day: Day,
@@ -31,13 +43,18 @@ mod args {
         All {
             release: bool,
         },
+        Stars {
+            mark: Option<(Day, u8)>,
+        },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
@@ -47,6 +64,16 @@ mod args {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
             },
+            Some("stars") => match args.subcommand()?.as_deref() {
+                Some("mark") => AppArguments::Stars {
+                    mark: Some((args.free_from_str()?, args.free_from_str()?)),
+                },
+                Some(x) => {
+                    eprintln!("Unknown stars command: {x}");
+                    process::exit(1);
+                }
+                None => AppArguments::Stars { mark: None },
+            },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
@@ -60,14 +87,42 @@ mod args {
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+            Some("encrypt") => AppArguments::Encrypt {
+                day: args.opt_free_from_str()?,
+            },
+            Some("decrypt") => AppArguments::Decrypt {
+                day: args.opt_free_from_str()?,
             },
-            Some("scaffold") => AppArguments::Scaffold {
+            Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
+                refresh: args.contains("--refresh"),
             },
+            Some("scaffold") => {
+                let defaults = TemplateOptions::default();
+                let part_one_type: Option<String> = args.opt_value_from_str("--type")?;
+                let part_two_type: Option<String> = args.opt_value_from_str("--type-two")?;
+
+                AppArguments::Scaffold {
+                    day: args.free_from_str()?,
+                    download: args.contains("--download"),
+                    options: ScaffoldOptions {
+                        overwrite: args.contains("--overwrite"),
+                        reset_data: args.contains("--reset-data"),
+                        dry_run: args.contains("--dry-run"),
+                        template: TemplateOptions {
+                            name: args
+                                .opt_value_from_str("--template")?
+                                .unwrap_or(defaults.name),
+                            part_two_type: part_two_type
+                                .or_else(|| part_one_type.clone())
+                                .unwrap_or(defaults.part_two_type),
+                            part_one_type: part_one_type.unwrap_or(defaults.part_one_type),
+                            example_part_one: args.opt_value_from_str("--example-one")?,
+                            example_part_two: args.opt_value_from_str("--example-two")?,
+                        },
+                    },
+                }
+            }
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
@@ -75,7 +130,9 @@ mod args {
                 dhat: args.contains("--dhat"),
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today {
+                wait: args.contains("--wait"),
+            },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -103,16 +160,19 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All { release } => all::handle(release),
+            AppArguments::Stars { mark } => stars::handle(mark),
             AppArguments::Time { day, all, store } => time::handle(day, all, store),
             AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+            AppArguments::Encrypt { day } => encrypt::handle(day),
+            AppArguments::Decrypt { day } => decrypt::handle(day),
+            AppArguments::Read { day, refresh } => read::handle(day, refresh),
             AppArguments::Scaffold {
                 day,
                 download,
-                overwrite,
+                options,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
+                scaffold::handle(day, &options);
+                if download && !options.dry_run {
                     download::handle(day);
                 }
             }
@@ -123,17 +183,20 @@ fn main() {
                 submit,
             } => solve::handle(day, release, dhat, submit),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
+            AppArguments::Today { wait: true } => wait::handle(),
+            #[cfg(feature = "today")]
+            AppArguments::Today { wait: false } => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
+                        scaffold::handle(day, &Default::default());
                         download::handle(day);
-                        read::handle(day)
+                        read::handle(day, false)
                     }
                     None => {
                         eprintln!(
                             "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
+                            the {}th of december. Please use `scaffold` with a specific day.",
+                            Event::current().last_day()
                         );
                         process::exit(1)
                     }
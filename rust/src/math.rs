@@ -0,0 +1,359 @@
+//! Number theory and small exact linear systems.
+//!
+//! Intermediate results are computed with `i128`, so that the functions do not overflow for
+//! any `i64` input whose result fits into an `i64`.
+
+/// The solutions of a linear system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solution<T> {
+    /// The system is inconsistent or has no integer solution.
+    None,
+    Unique(T),
+    /// The equations are linearly dependent, so every point on a line (or the plane) solves them.
+    Infinite,
+}
+
+pub fn gcd(a: i64, b: i64) -> i64 {
+    egcd(a, b).0
+}
+
+/// The least common multiple of `a` and `b`, which is `0` if either of them is `0`.
+///
+/// # Panics
+///
+/// Panics if the result does not fit into an `i64`.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    let lcm = (a as i128 / gcd(a, b) as i128 * b as i128).abs();
+    i64::try_from(lcm).expect("the least common multiple overflows an i64")
+}
+
+/// The extended euclidean algorithm. Returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    (old_r as i64, old_x as i64, old_y as i64)
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inv(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves a system of congruences `x ≡ r (mod m)` with the chinese remainder theorem.
+/// The moduli do not need to be coprime.
+///
+/// Returns `(x, lcm)` with the smallest non-negative solution `x` and the modulus of all solutions,
+/// or `None` if the congruences contradict each other.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let mut result: (i128, i128) = (0, 1);
+
+    for (residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (r1, m1) = result;
+        let (r2, m2) = (residue.rem_euclid(modulus) as i128, modulus as i128);
+
+        let g = gcd(m1 as i64, m2 as i64) as i128;
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+
+        let step = m2 / g;
+        let inverse = mod_inv(((m1 / g) % step) as i64, step as i64)? as i128;
+        let k = ((r2 - r1) / g % step * inverse).rem_euclid(step);
+        let lcm = m1 * step;
+
+        result = ((r1 + m1 * k).rem_euclid(lcm), lcm);
+        i64::try_from(lcm).ok()?;
+    }
+
+    Some((result.0 as i64, result.1 as i64))
+}
+
+/// Solves `matrix * [x, y] = rhs` over the integers.
+///
+/// A unique rational solution that is not integral yields [`Solution::None`].
+pub fn solve_2x2(matrix: [[i64; 2]; 2], rhs: [i64; 2]) -> Solution<[i64; 2]> {
+    let [[a, b], [c, d]] = matrix.map(|row| row.map(i128::from));
+    let [e, f] = rhs.map(i128::from);
+
+    let determinant = a * d - b * c;
+    if determinant == 0 {
+        // both rows must describe the same line, i.e. the augmented matrix has rank < 2.
+        let is_consistent = a * f - c * e == 0 && b * f - d * e == 0;
+        // the system then reduces to a single equation, which needs an integer solution.
+        let (row, value) = if matrix[0] != [0, 0] {
+            (matrix[0], rhs[0])
+        } else {
+            (matrix[1], rhs[1])
+        };
+        let has_integer_points = match gcd(row[0], row[1]) {
+            0 => e == 0 && f == 0,
+            g => value % g == 0,
+        };
+        return if is_consistent && has_integer_points {
+            Solution::Infinite
+        } else {
+            Solution::None
+        };
+    }
+
+    let x = e * d - b * f;
+    let y = a * f - e * c;
+    if x % determinant != 0 || y % determinant != 0 {
+        return Solution::None;
+    }
+
+    match (
+        i64::try_from(x / determinant),
+        i64::try_from(y / determinant),
+    ) {
+        (Ok(x), Ok(y)) => Solution::Unique([x, y]),
+        _ => Solution::None,
+    }
+}
+
+/// All integer solutions of `a * x + b * y = c`, i.e. `(x + k * step_x, y - k * step_y)` for any `k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diophantine {
+    pub x: i64,
+    pub y: i64,
+    pub step_x: i64,
+    pub step_y: i64,
+}
+
+impl Diophantine {
+    pub fn at(&self, k: i64) -> (i64, i64) {
+        (self.x + k * self.step_x, self.y - k * self.step_y)
+    }
+}
+
+/// Solves the linear diophantine equation `a * x + b * y = c`.
+/// Returns `None` if there is no integer solution or if `a` and `b` are both `0`.
+pub fn solve_diophantine(a: i64, b: i64, c: i64) -> Option<Diophantine> {
+    let (g, x, y) = egcd(a, b);
+    if g == 0 || c % g != 0 {
+        return None;
+    }
+
+    let scale = (c / g) as i128;
+    Some(Diophantine {
+        x: i64::try_from(x as i128 * scale).ok()?,
+        y: i64::try_from(y as i128 * scale).ok()?,
+        step_x: b / g,
+        step_y: a / g,
+    })
+}
+
+/// The number of decimal digits of `n`, where `0` has a single digit.
+pub fn digit_count(n: u64) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Splits `n` into its leading digits and its last `count` digits, e.g. `(1234, 1)` -> `(123, 4)`.
+pub fn split_digits(n: u64, count: u32) -> (u64, u64) {
+    match 10u64.checked_pow(count) {
+        Some(divisor) => (n / divisor, n % divisor),
+        None => (0, n),
+    }
+}
+
+/// The decimal digits of `n`, most significant first.
+pub fn digits(n: u64) -> Vec<u8> {
+    let mut digits: Vec<u8> = std::iter::successors(Some(n), |&n| (n >= 10).then_some(n / 10))
+        .map(|n| (n % 10) as u8)
+        .collect();
+    digits.reverse();
+    digits
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{
+        crt, digit_count, digits, egcd, gcd, lcm, mod_inv, solve_2x2, solve_diophantine,
+        split_digits, Solution,
+    };
+
+    #[test]
+    fn solves_unique_systems() {
+        // the first claw machine of day 13.
+        let solution = solve_2x2([[94, 22], [34, 67]], [8400, 5400]);
+        assert_eq!(solution, Solution::Unique([80, 40]));
+        // the second one has no integer solution.
+        let solution = solve_2x2([[26, 67], [66, 21]], [12748, 12176]);
+        assert_eq!(solution, Solution::None);
+    }
+
+    #[test]
+    fn solves_dependent_systems() {
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), Solution::Infinite);
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 7]), Solution::None);
+        // the line 2x + 4y = 3 has no integer points.
+        assert_eq!(solve_2x2([[2, 4], [2, 4]], [3, 3]), Solution::None);
+        assert_eq!(solve_2x2([[0, 0], [2, 4]], [0, 6]), Solution::Infinite);
+        assert_eq!(solve_2x2([[0, 0], [0, 0]], [0, 0]), Solution::Infinite);
+        assert_eq!(solve_2x2([[0, 0], [0, 0]], [1, 0]), Solution::None);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+    }
+
+    #[test]
+    fn handles_digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(u64::MAX), 20);
+        assert_eq!(split_digits(253000, 3), (253, 0));
+        assert_eq!(split_digits(7, 25), (0, 7));
+        assert_eq!(digits(1024), vec![1, 0, 2, 4]);
+        assert_eq!(digits(0), vec![0]);
+    }
+
+    #[test]
+    fn computes_inverses() {
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(2, 4), None);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm(i64::MIN + 1, 1), i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn rejects_overflowing_multiples() {
+        lcm(i64::MIN, 1);
+    }
+
+    proptest! {
+        #[test]
+        fn egcd_satisfies_bezout(a in -1_000_000_000i64..1_000_000_000, b in -1_000_000_000i64..1_000_000_000) {
+            let (g, x, y) = egcd(a, b);
+            prop_assert_eq!(a as i128 * x as i128 + b as i128 * y as i128, g as i128);
+            prop_assert!(g >= 0);
+            if g != 0 {
+                prop_assert_eq!(a % g, 0);
+                prop_assert_eq!(b % g, 0);
+            }
+        }
+
+        #[test]
+        fn lcm_is_a_common_multiple(a in 1i64..100_000, b in 1i64..100_000) {
+            let l = lcm(a, b);
+            prop_assert_eq!(l % a, 0);
+            prop_assert_eq!(l % b, 0);
+            prop_assert_eq!(l as i128 * gcd(a, b) as i128, a as i128 * b as i128);
+        }
+
+        #[test]
+        fn inverses_multiply_to_one(a in -10_000i64..10_000, m in 2i64..10_000) {
+            match mod_inv(a, m) {
+                Some(inverse) => prop_assert_eq!((a * inverse).rem_euclid(m), 1),
+                None => prop_assert_ne!(gcd(a, m), 1),
+            }
+        }
+
+        #[test]
+        fn crt_satisfies_all_congruences(
+            x in 0i64..1_000_000,
+            moduli in proptest::collection::vec(1i64..1000, 1..5),
+        ) {
+            let (solution, modulus) = crt(moduli.iter().map(|&m| (x % m, m))).unwrap();
+            for &m in &moduli {
+                prop_assert_eq!(solution % m, x % m);
+                prop_assert_eq!(modulus % m, 0);
+            }
+            prop_assert!(solution < modulus);
+        }
+
+        #[test]
+        fn unique_solutions_solve_the_system(
+            x in -10_000i64..10_000,
+            y in -10_000i64..10_000,
+            matrix in proptest::array::uniform2(proptest::array::uniform2(-1000i64..1000)),
+        ) {
+            let rhs = [matrix[0][0] * x + matrix[0][1] * y, matrix[1][0] * x + matrix[1][1] * y];
+            match solve_2x2(matrix, rhs) {
+                Solution::Unique(solution) => prop_assert_eq!(solution, [x, y]),
+                Solution::Infinite => {
+                    prop_assert_eq!(matrix[0][0] * matrix[1][1], matrix[0][1] * matrix[1][0])
+                }
+                Solution::None => prop_assert!(false, "system has a solution"),
+            }
+        }
+
+        #[test]
+        fn dependent_systems_need_integer_points(
+            row in proptest::array::uniform2(-1000i64..1000),
+            factor in -10i64..10,
+            value in -100_000i64..100_000,
+        ) {
+            let matrix = [row, [row[0] * factor, row[1] * factor]];
+            let expected = match gcd(row[0], row[1]) {
+                0 if value != 0 => Solution::None,
+                0 => Solution::Infinite,
+                g if value % g == 0 => Solution::Infinite,
+                _ => Solution::None,
+            };
+            prop_assert_eq!(solve_2x2(matrix, [value, value * factor]), expected);
+        }
+
+        #[test]
+        fn diophantine_solutions_solve_the_equation(
+            a in -1000i64..1000,
+            b in -1000i64..1000,
+            c in -100_000i64..100_000,
+            k in -100i64..100,
+        ) {
+            match solve_diophantine(a, b, c) {
+                Some(solutions) => {
+                    let (x, y) = solutions.at(k);
+                    prop_assert_eq!(a * x + b * y, c);
+                }
+                None => prop_assert!(gcd(a, b) == 0 || c % gcd(a, b) != 0),
+            }
+        }
+
+        #[test]
+        fn splits_digits_back_together(n in any::<u64>()) {
+            let count = digit_count(n);
+            prop_assert_eq!(digits(n).len() as u32, count);
+            prop_assert_eq!(count as usize, n.to_string().len());
+
+            let (high, low) = split_digits(n, count / 2);
+            let divisor = 10u128.pow(count / 2);
+            prop_assert!(u128::from(low) < divisor);
+            prop_assert_eq!(u128::from(high) * divisor + u128::from(low), u128::from(n));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Memoization for recursive solutions.
+//!
+//! Write the recursion as a function that receives the cache and wraps its body in
+//! [`Memo::get_or_compute`]. Nested calls pass the cache on:
+//!
+//! ```
+//! use advent_of_code::memo::Memo;
+//!
+//! fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     if n < 2 {
+//!         return n;
+//!     }
+//!     memo.get_or_compute(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+//! }
+//!
+//! assert_eq!(fibonacci(&mut Memo::new(), 90), 2880067194370816120);
+//! ```
+//!
+//! When a `Memo` is dropped, its hit and miss counts are recorded, so that the runner can print
+//! them below the result of a part.
+use std::{cell::Cell, collections::HashMap, fmt::Display, hash::Hash, ops::AddAssign};
+
+thread_local! {
+    static RECORDED: Cell<Option<CacheStats>> = const { Cell::new(None) };
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl AddAssign for CacheStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.hits += rhs.hits;
+        self.misses += rhs.misses;
+    }
+}
+
+impl Display for CacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// Returns the statistics of all caches dropped on this thread since the last call, if any.
+pub fn take_stats() -> Option<CacheStats> {
+    RECORDED.with(Cell::take)
+}
+
+/// A cache of computed values, keyed by the arguments of a recursive function.
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: CacheStats,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Returns the cached value for `key` or computes it with `compute`.
+    /// `compute` receives the cache, so that it can recurse.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        let stats = self.stats;
+        // the thread local is gone if the memo is dropped during thread teardown.
+        let _ = RECORDED.try_with(|recorded| {
+            let mut total = recorded.take().unwrap_or_default();
+            total += stats;
+            recorded.set(Some(total));
+        });
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{take_stats, CacheStats, Memo};
+
+    fn paths(memo: &mut Memo<(u32, u32), u64>, x: u32, y: u32) -> u64 {
+        if x == 0 || y == 0 {
+            return 1;
+        }
+        memo.get_or_compute((x, y), |memo| paths(memo, x - 1, y) + paths(memo, x, y - 1))
+    }
+
+    #[test]
+    fn memoizes_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601080390);
+        assert_eq!(memo.len(), 256);
+        assert_eq!(memo.get(&(1, 1)), Some(&2));
+        assert_eq!(memo.stats().misses, 256);
+        assert!(memo.stats().hits > 0);
+    }
+
+    #[test]
+    fn records_stats_on_drop() {
+        take_stats();
+        {
+            let mut memo = Memo::new();
+            paths(&mut memo, 2, 2);
+        }
+        assert_eq!(take_stats(), Some(CacheStats { hits: 1, misses: 4 }));
+        assert_eq!(take_stats(), None);
+    }
+
+    #[test]
+    fn formats_stats() {
+        let stats = CacheStats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+    }
+}
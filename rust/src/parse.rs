@@ -0,0 +1,179 @@
+//! Helpers for the recurring shapes of puzzle inputs.
+use std::{fmt::Display, str::FromStr};
+
+use crate::grid::Grid;
+
+/// Extracts all integers from `line`, e.g. `p=0,4 v=3,-3` yields `0, 4, 3, -3`.
+///
+/// A `-` directly in front of a number is read as its sign, unless it follows another digit
+/// (as in ranges like `1-3`). Unsigned types ignore the sign.
+pub fn ints<T: FromStr>(line: &str) -> impl Iterator<Item = T> + '_ {
+    let bytes = line.as_bytes();
+    let mut start = 0;
+
+    std::iter::from_fn(move || {
+        while start < bytes.len() {
+            let begin = start;
+            if !bytes[begin].is_ascii_digit() {
+                start += 1;
+                continue;
+            }
+
+            let mut end = begin;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+            start = end;
+
+            let is_negative = begin > 0
+                && bytes[begin - 1] == b'-'
+                && (begin < 2 || !bytes[begin - 2].is_ascii_digit());
+            if is_negative {
+                if let Ok(value) = line[begin - 1..end].parse() {
+                    return Some(value);
+                }
+            }
+            if let Ok(value) = line[begin..end].parse() {
+                return Some(value);
+            }
+        }
+        None
+    })
+}
+
+/// Splits `input` into blocks separated by blank lines. Works with `\n` and `\r\n` line endings.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        let mut section: Option<(usize, usize)> = None;
+        let mut offset = 0;
+
+        for line in rest.split_inclusive('\n') {
+            let is_blank = line.trim().is_empty();
+            match (&mut section, is_blank) {
+                (None, true) => {}
+                (None, false) => section = Some((offset, offset + line.len())),
+                (Some((_, end)), false) => *end = offset + line.len(),
+                (Some(_), true) => break,
+            }
+            offset += line.len();
+        }
+
+        let (start, end) = section?;
+        let found = rest[start..end].trim_end();
+        rest = &rest[end..];
+        Some(found)
+    })
+}
+
+/// Iterates over the characters of `line` together with the slice each of them occupies,
+/// e.g. to report an invalid tile with [`AocError::at`](crate::error::AocError::at).
+pub fn char_slices(line: &str) -> impl Iterator<Item = (char, &str)> {
+    line.char_indices()
+        .map(move |(index, c)| (c, &line[index..index + c.len_utf8()]))
+}
+
+/// Parses a character map into a grid of chars.
+pub fn grid_chars(input: &str) -> Grid<char> {
+    Grid::parse(input, |c| c)
+}
+
+/// A line of the input that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to parse line {} ({:?}): {}",
+            self.line, self.text, self.reason
+        )
+    }
+}
+
+/// Parses every non-empty line of `input` with `FromStr`, reporting the first line that fails.
+pub fn lines_as<T>(input: &str) -> Result<Vec<T>, LineError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Debug,
+{
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.trim().parse().map_err(|e| LineError {
+                line: index + 1,
+                text: line.to_string(),
+                reason: format!("{e:?}"),
+            })
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{char_slices, grid_chars, ints, lines_as, sections};
+
+    #[test]
+    fn extracts_ints() {
+        let values: Vec<i32> = ints("p=0,4 v=3,-3").collect();
+        assert_eq!(values, vec![0, 4, 3, -3]);
+
+        let values: Vec<i64> = ints("Button A: X+94, Y+34").collect();
+        assert_eq!(values, vec![94, 34]);
+    }
+
+    #[test]
+    fn handles_signs() {
+        let values: Vec<i32> = ints("1-3 -5 x-7").collect();
+        assert_eq!(values, vec![1, 3, -5, -7]);
+
+        let values: Vec<u32> = ints("v=3,-3").collect();
+        assert_eq!(values, vec![3, 3]);
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+        assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+
+        let input = "a\r\nb\r\n\r\nc\r\n";
+        assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\r\nb", "c"]);
+
+        assert_eq!(sections("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn slices_chars() {
+        let line = "a→b";
+        let slices: Vec<_> = char_slices(line).collect();
+        assert_eq!(slices, vec![('a', "a"), ('→', "→"), ('b', "b")]);
+        assert_eq!(slices[2].1.as_ptr(), line[4..].as_ptr());
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid_chars("#.\r\n.#\r\n");
+        assert_eq!(grid.dim(), (2, 2));
+        assert_eq!(grid[(1, 1)], '#');
+    }
+
+    #[test]
+    fn parses_lines() {
+        assert_eq!(lines_as::<u8>("1\n2\n\n3\n"), Ok(vec![1, 2, 3]));
+
+        let error = lines_as::<u8>("1\n300\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.text, "300");
+    }
+}
@@ -0,0 +1,354 @@
+//! Shortest paths over an arbitrary state space.
+//!
+//! Implement [`Problem`] for the puzzle, then run [`bfs`], [`dijkstra`] or [`astar`].
+//! All of them return a [`Search`] that holds the distance of every settled state and the
+//! predecessors of every state on a shortest path, so that paths and all tiles on *any* optimal
+//! path can be recovered without a separate backwards walk.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+pub type Cost = u32;
+
+pub trait Problem {
+    type State: Clone + Eq + Hash;
+
+    /// The states reachable from `state`, together with the cost of the move.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, Cost)>;
+
+    /// Whether `state` ends the search. Searches without a goal explore every reachable state.
+    fn is_goal(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// A lower bound of the remaining cost from `state` to a goal, used by [`astar`].
+    ///
+    /// It must be consistent: it may drop by at most the cost of a move, and be `0` at goals.
+    /// [`astar`] never expands a state twice, so a heuristic that merely never overestimates
+    /// can settle a state too early and return a path that is not optimal.
+    fn heuristic(&self, _state: &Self::State) -> Cost {
+        0
+    }
+}
+
+/// The result of a search.
+#[derive(Debug, Clone)]
+pub struct Search<S> {
+    distances: HashMap<S, Cost>,
+    /// For every state, all states it can be reached from on a shortest path.
+    predecessors: HashMap<S, Vec<S>>,
+    goals: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new() -> Self {
+        Self {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            goals: Vec::new(),
+        }
+    }
+
+    /// The cost of the cheapest path to a goal.
+    pub fn cost(&self) -> Option<Cost> {
+        self.goals.first().map(|goal| self.distances[goal])
+    }
+
+    /// The goals that were reached at the lowest cost.
+    pub fn goals(&self) -> &[S] {
+        &self.goals
+    }
+
+    /// The cost of the cheapest path to `state`, if it was reached.
+    pub fn distance(&self, state: &S) -> Option<Cost> {
+        self.distances.get(state).copied()
+    }
+
+    /// Iterates over all reached states and their distances.
+    pub fn distances(&self) -> impl Iterator<Item = (&S, Cost)> {
+        self.distances.iter().map(|(state, &cost)| (state, cost))
+    }
+
+    /// The states `state` is reached from on a shortest path.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// One of the cheapest paths to a goal, starting with a start state.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goals.first()?)
+    }
+
+    /// One of the cheapest paths to `state`, starting with a start state.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        self.distances.get(state)?;
+
+        let mut path = vec![state.clone()];
+        while let Some(previous) = self.predecessors(path.last().unwrap()).first() {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// All states that lie on at least one of the cheapest paths to a goal.
+    pub fn on_optimal_paths(&self) -> HashSet<S> {
+        let mut seen: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+
+        while let Some(state) = stack.pop() {
+            for previous in self.predecessors(&state) {
+                if seen.insert(previous.clone()) {
+                    stack.push(previous.clone());
+                }
+            }
+        }
+        seen
+    }
+
+    /// Records an edge into `next` with the total cost `cost`. Returns whether `next` improved.
+    ///
+    /// Free edges can lead back to a state at the same cost. Such edges are not recorded for
+    /// starts, which have no predecessors, nor for loops, so that every path ends at a start.
+    fn relax(&mut self, state: &S, next: S, cost: Cost) -> bool {
+        match self.distances.get(&next) {
+            Some(&known) if cost > known => false,
+            Some(&known) if cost == known => {
+                if let Some(predecessors) = self.predecessors.get_mut(&next) {
+                    if next != *state {
+                        predecessors.push(state.clone());
+                    }
+                }
+                false
+            }
+            _ => {
+                self.distances.insert(next.clone(), cost);
+                self.predecessors.insert(next, vec![state.clone()]);
+                true
+            }
+        }
+    }
+}
+
+/// Breadth-first search, which ignores the costs of `problem` and counts every move as `1`.
+pub fn bfs<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = P::State>,
+) -> Search<P::State> {
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        search.distances.insert(start.clone(), 0);
+        queue.push_back(start);
+    }
+
+    while let Some(state) = queue.pop_front() {
+        let cost = search.distances[&state];
+        if search.cost().is_some_and(|best| cost > best) {
+            break;
+        }
+        if problem.is_goal(&state) {
+            search.goals.push(state);
+            continue;
+        }
+
+        for (next, _) in problem.successors(&state) {
+            if search.relax(&state, next.clone(), cost + 1) {
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm for non-negative costs.
+pub fn dijkstra<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = P::State>,
+) -> Search<P::State> {
+    explore(problem, starts, |_| 0)
+}
+
+/// A* search, guided by [`Problem::heuristic`], which must be consistent.
+pub fn astar<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = P::State>,
+) -> Search<P::State> {
+    explore(problem, starts, |state| problem.heuristic(state))
+}
+
+fn explore<P: Problem>(
+    problem: &P,
+    starts: impl IntoIterator<Item = P::State>,
+    heuristic: impl Fn(&P::State) -> Cost,
+) -> Search<P::State> {
+    let mut search = Search::new();
+    // states are not required to be `Ord`, so the queue refers to them by index.
+    let mut queued = Vec::new();
+    let mut queue = BinaryHeap::new();
+    let mut settled = HashSet::new();
+
+    for start in starts {
+        search.distances.insert(start.clone(), 0);
+        queue.push(Reverse((heuristic(&start), queued.len())));
+        queued.push(start);
+    }
+
+    while let Some(Reverse((estimate, index))) = queue.pop() {
+        if search.cost().is_some_and(|best| estimate > best) {
+            break;
+        }
+
+        let state = queued[index].clone();
+        if !settled.insert(state.clone()) {
+            continue;
+        }
+        if problem.is_goal(&state) {
+            search.goals.push(state);
+            continue;
+        }
+
+        let cost = search.distances[&state];
+        for (next, step) in problem.successors(&state) {
+            let next_cost = cost + step;
+            if search.relax(&state, next.clone(), next_cost) {
+                queue.push(Reverse((next_cost + heuristic(&next), queued.len())));
+                queued.push(next);
+            }
+        }
+    }
+    search
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra, Cost, Problem};
+
+    /// A diamond `0 -> {1, 2} -> 3` where both paths are optimal, plus an expensive detour via 4.
+    struct Diamond;
+
+    impl Problem for Diamond {
+        type State = u8;
+
+        fn successors(&self, state: &u8) -> Vec<(u8, Cost)> {
+            match state {
+                0 => vec![(1, 1), (2, 1), (4, 5)],
+                1 | 2 => vec![(3, 1)],
+                4 => vec![(3, 1)],
+                _ => vec![],
+            }
+        }
+
+        fn is_goal(&self, state: &u8) -> bool {
+            *state == 3
+        }
+    }
+
+    /// The start 0 and 1 are connected by free moves in both directions, and 1 loops onto itself.
+    struct FreeCycle;
+
+    impl Problem for FreeCycle {
+        type State = u8;
+
+        fn successors(&self, state: &u8) -> Vec<(u8, Cost)> {
+            match state {
+                0 => vec![(1, 0)],
+                1 => vec![(0, 0), (1, 0), (2, 1)],
+                _ => vec![],
+            }
+        }
+
+        fn is_goal(&self, state: &u8) -> bool {
+            *state == 2
+        }
+    }
+
+    /// A line of numbers, where the distance to 10 is a perfect heuristic.
+    struct Line;
+
+    impl Problem for Line {
+        type State = i32;
+
+        fn successors(&self, state: &i32) -> Vec<(i32, Cost)> {
+            vec![(state - 1, 1), (state + 1, 1)]
+        }
+
+        fn is_goal(&self, state: &i32) -> bool {
+            *state == 10
+        }
+
+        fn heuristic(&self, state: &i32) -> Cost {
+            state.abs_diff(10)
+        }
+    }
+
+    #[test]
+    fn finds_cheapest_cost() {
+        let search = dijkstra(&Diamond, [0]);
+        assert_eq!(search.cost(), Some(2));
+        assert_eq!(search.goals(), &[3]);
+        assert_eq!(search.distance(&4), Some(5));
+    }
+
+    #[test]
+    fn recovers_paths() {
+        let search = dijkstra(&Diamond, [0]);
+        let path = search.path().unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!((path[0], path[2]), (0, 3));
+
+        let mut predecessors = search.predecessors(&3).to_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, vec![1, 2]);
+    }
+
+    #[test]
+    fn ends_paths_at_a_start_despite_free_cycles() {
+        let search = dijkstra(&FreeCycle, [0]);
+        assert_eq!(search.cost(), Some(1));
+        assert_eq!(search.path(), Some(vec![0, 1, 2]));
+        assert!(search.predecessors(&0).is_empty());
+        assert_eq!(search.predecessors(&1), &[0]);
+        assert_eq!(search.on_optimal_paths().len(), 3);
+    }
+
+    #[test]
+    fn collects_all_optimal_states() {
+        let mut states: Vec<_> = dijkstra(&Diamond, [0])
+            .on_optimal_paths()
+            .into_iter()
+            .collect();
+        states.sort();
+        assert_eq!(states, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn counts_moves_with_bfs() {
+        let search = bfs(&Diamond, [0]);
+        assert_eq!(search.cost(), Some(2));
+        assert_eq!(search.on_optimal_paths().len(), 5);
+    }
+
+    #[test]
+    fn guides_astar() {
+        let search = astar(&Line, [0]);
+        assert_eq!(search.cost(), Some(10));
+        assert_eq!(search.path().unwrap(), (0..=10).collect::<Vec<_>>());
+        // a perfect heuristic never expands states away from the goal.
+        assert_eq!(search.distance(&-2), None);
+    }
+
+    #[test]
+    fn searches_from_any_state() {
+        let search = dijkstra(&Diamond, [4]);
+        assert_eq!(search.cost(), Some(1));
+        let search = dijkstra(&Diamond, [3]);
+        assert_eq!(search.cost(), Some(0));
+        assert_eq!(search.path(), Some(vec![3]));
+    }
+}
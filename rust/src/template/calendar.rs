@@ -0,0 +1,148 @@
+use crate::template::Day;
+
+/// The first event that was released with 12 puzzles instead of 25.
+const FIRST_SHORT_EVENT: u16 = 2025;
+
+/// The event calendar of an advent of code year, i.e. how many puzzles were released
+/// and how many parts each of them has.
+///
+/// The year is read from the `AOC_YEAR` variable in `.cargo/config.toml` at compile time.
+/// If it is not set, the classic calendar with 25 days is assumed.
+///
+/// ```
+/// # use advent_of_code::template::Event;
+/// let event = Event::for_year(2024);
+/// assert_eq!(event.last_day(), 25);
+/// assert_eq!(event.parts(25), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    year: Option<u16>,
+    days: u8,
+}
+
+impl Event {
+    /// Returns the calendar of the configured event.
+    pub const fn current() -> Self {
+        match option_env!("AOC_YEAR") {
+            Some(year) => match parse_year(year) {
+                Some(year) => Self::for_year(year),
+                None => Self::classic(),
+            },
+            None => Self::classic(),
+        }
+    }
+
+    /// Returns the calendar of the event held in `year`.
+    pub const fn for_year(year: u16) -> Self {
+        let days = if year >= FIRST_SHORT_EVENT { 12 } else { 25 };
+        Self {
+            year: Some(year),
+            days,
+        }
+    }
+
+    /// The calendar with 25 days that is assumed when no year is configured.
+    pub(crate) const fn classic() -> Self {
+        Self {
+            year: None,
+            days: 25,
+        }
+    }
+
+    /// The year of the event, if one is configured.
+    pub const fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    /// The number of the last puzzle of the event.
+    pub const fn last_day(&self) -> u8 {
+        self.days
+    }
+
+    /// Checks whether `day` is a puzzle of this event.
+    pub const fn contains(&self, day: u8) -> bool {
+        day != 0 && day <= self.days
+    }
+
+    /// The number of parts of the puzzle released on `day`.
+    /// The last puzzle of every event only has a single part.
+    pub const fn parts(&self, day: u8) -> u8 {
+        if day == self.days {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Checks whether `day` has a part two.
+    pub fn has_part_two(&self, day: Day) -> bool {
+        self.parts(day.into_inner()) == 2
+    }
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+/// Parses a year in a const context, as `str::parse` is not `const`.
+const fn parse_year(s: &str) -> Option<u16> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() || bytes.len() > 4 {
+        return None;
+    }
+
+    let mut year: u16 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if !byte.is_ascii_digit() {
+            return None;
+        }
+        year = year * 10 + (byte - b'0') as u16;
+        i += 1;
+    }
+    Some(year)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_year, Event};
+
+    #[test]
+    fn parses_years() {
+        assert_eq!(parse_year("2024"), Some(2024));
+        assert_eq!(parse_year(""), None);
+        assert_eq!(parse_year("20x4"), None);
+        assert_eq!(parse_year("20245"), None);
+    }
+
+    #[test]
+    fn handles_classic_events() {
+        let event = Event::for_year(2024);
+        assert_eq!(event.last_day(), 25);
+        assert!(event.contains(25));
+        assert!(!event.contains(26));
+        assert_eq!(event.parts(24), 2);
+        assert_eq!(event.parts(25), 1);
+    }
+
+    #[test]
+    fn handles_short_events() {
+        let event = Event::for_year(2025);
+        assert_eq!(event.last_day(), 12);
+        assert!(event.contains(12));
+        assert!(!event.contains(13));
+        assert_eq!(event.parts(11), 2);
+        assert_eq!(event.parts(12), 1);
+    }
+
+    #[test]
+    fn reads_configured_year() {
+        assert_eq!(Event::current().year(), Some(2024));
+    }
+}
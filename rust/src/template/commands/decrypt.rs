@@ -0,0 +1,35 @@
+use std::process;
+
+use crate::template::{
+    all_days,
+    encryption::{self, InputKey},
+    Day,
+};
+
+pub fn handle(day: Option<Day>) {
+    let key = match InputKey::load() {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("Failed to load input key: {e}");
+            process::exit(1);
+        }
+    };
+
+    let days: Vec<Day> = day.map_or_else(|| all_days().collect(), |day| vec![day]);
+
+    for path in days.into_iter().flat_map(encryption::private_files) {
+        if !encryption::encrypted_path(&path).exists() {
+            continue;
+        }
+
+        match encryption::decrypt_file(&path, &key) {
+            Ok(()) => {
+                println!("Decrypted \"{}\"", path.display());
+            }
+            Err(e) => {
+                eprintln!("Failed to decrypt \"{}\": {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+}
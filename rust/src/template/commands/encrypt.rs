@@ -0,0 +1,39 @@
+use std::process;
+
+use crate::template::{
+    all_days,
+    encryption::{self, InputKey},
+    Day,
+};
+
+pub fn handle(day: Option<Day>) {
+    let key = match InputKey::load() {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("Failed to load input key: {e}");
+            process::exit(1);
+        }
+    };
+
+    let days: Vec<Day> = day.map_or_else(|| all_days().collect(), |day| vec![day]);
+
+    for path in days.into_iter().flat_map(encryption::private_files) {
+        if !path.exists() {
+            continue;
+        }
+
+        match encryption::encrypt_file(&path, &key) {
+            Ok(target) => {
+                println!(
+                    "Encrypted \"{}\" to \"{}\"",
+                    path.display(),
+                    target.display()
+                );
+            }
+            Err(e) => {
+                eprintln!("Failed to encrypt \"{}\": {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+}
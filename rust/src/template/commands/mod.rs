@@ -1,6 +1,11 @@
 pub mod all;
+pub mod decrypt;
 pub mod download;
+pub mod encrypt;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod stars;
 pub mod time;
+#[cfg(feature = "today")]
+pub mod wait;
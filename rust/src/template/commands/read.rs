@@ -1,8 +1,24 @@
-use std::process;
+use std::{
+    env, fs,
+    io::{stdout, IsTerminal, Write},
+    process::{self, Command, Stdio},
+};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, markdown, Day};
+
+/// Output longer than this is shown in a pager if `LINES` is not set.
+const DEFAULT_TERMINAL_HEIGHT: usize = 40;
+
+pub fn handle(day: Day, refresh: bool) {
+    let puzzle_path = format!("data/puzzles/{day}.md");
+
+    if !refresh {
+        if let Ok(puzzle) = fs::read_to_string(&puzzle_path) {
+            show(&markdown::render(&puzzle));
+            return;
+        }
+    }
 
-pub fn handle(day: Day) {
     if aoc_cli::check().is_err() {
         eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
@@ -13,3 +29,39 @@ pub fn handle(day: Day) {
         process::exit(1);
     };
 }
+
+/// Prints `text`, using a pager if it does not fit on the screen.
+fn show(text: &str) {
+    let height = env::var("LINES")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(DEFAULT_TERMINAL_HEIGHT);
+
+    if !stdout().is_terminal() || text.lines().count() <= height || !page(text) {
+        println!("{text}");
+    }
+}
+
+/// Pipes `text` into `$PAGER` (`less -R` by default). Returns `false` if no pager could be started.
+fn page(text: &str) -> bool {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".into());
+    let mut args = pager.split_whitespace();
+    let Some(program) = args.next() else {
+        return false;
+    };
+
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        return false;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the user might quit the pager before all lines were written.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+
+    child.wait().is_ok()
+}
@@ -1,79 +1,445 @@
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, OpenOptions},
     io::Write,
+    path::{Path, PathBuf},
     process,
 };
 
 use crate::template::Day;
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+const DEFAULT_TEMPLATE: &str = "default";
+const TEMPLATES_DIR: &str = "templates";
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+/// Fallback for projects without a `templates/` directory.
+const MODULE_TEMPLATE: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/templates/default.txt"
+));
+
+/// Options that select and fill in the module template.
+#[derive(Debug, Clone)]
+pub struct TemplateOptions {
+    /// Name of a template in `templates/`, without the `.txt` extension.
+    pub name: String,
+    pub part_one_type: String,
+    pub part_two_type: String,
+    /// Expected answers for the example input.
+    pub example_part_one: Option<String>,
+    pub example_part_two: Option<String>,
+}
+
+/// How to scaffold a day.
+#[derive(Debug, Clone, Default)]
+pub struct ScaffoldOptions {
+    /// Replace an existing module.
+    pub overwrite: bool,
+    /// Empty inputs and examples that already have content.
+    pub reset_data: bool,
+    /// Only print the planned changes.
+    pub dry_run: bool,
+    pub template: TemplateOptions,
+}
+
+impl Default for TemplateOptions {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_TEMPLATE.into(),
+            part_one_type: "u32".into(),
+            part_two_type: "u32".into(),
+            example_part_one: None,
+            example_part_two: None,
+        }
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+fn load_template(name: &str) -> Result<String, String> {
+    let path = Path::new(TEMPLATES_DIR).join(format!("{name}.txt"));
+
+    match fs::read_to_string(&path) {
+        Ok(template) => Ok(template),
+        Err(_) if name == DEFAULT_TEMPLATE => Ok(MODULE_TEMPLATE.into()),
+        Err(_) => Err(format!(
+            "template \"{name}\" not found. Available templates: {}",
+            available_templates().join(", ")
+        )),
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn available_templates() -> Vec<String> {
+    let mut templates: Vec<String> = fs::read_dir(TEMPLATES_DIR)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "txt").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    templates.sort_unstable();
+    templates
+}
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+/// Reads the puzzle title (e.g. `Day 1: Historian Hysteria`) from the downloaded description.
+fn read_title(day: Day) -> Option<String> {
+    let puzzle = fs::read_to_string(format!("data/puzzles/{day}.md")).ok()?;
+    let heading = puzzle.lines().find(|line| line.contains("--- Day"))?;
+    let title = heading
+        .trim_start_matches(['#', ' ', '\\'])
+        .trim_matches([' ', '-']);
+    Some(title.to_string())
+}
+
+fn format_answer(answer: Option<&String>, return_type: &str) -> String {
+    match answer {
+        Some(answer) if return_type == "String" => format!("Some(\"{answer}\".to_string())"),
+        Some(answer) => format!("Some({answer})"),
+        None => "None".into(),
+    }
+}
+
+fn render_module(template: &str, day: Day, options: &TemplateOptions, title: &str) -> String {
+    template
+        .replace("%DAY_NUMBER%", &day.into_inner().to_string())
+        .replace("%TITLE%", title)
+        .replace("%PART_ONE_TYPE%", &options.part_one_type)
+        .replace("%PART_TWO_TYPE%", &options.part_two_type)
+        .replace(
+            "%EXAMPLE_PART_ONE%",
+            &format_answer(options.example_part_one.as_ref(), &options.part_one_type),
+        )
+        .replace(
+            "%EXAMPLE_PART_TWO%",
+            &format_answer(options.example_part_two.as_ref(), &options.part_two_type),
+        )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Create,
+    Overwrite,
+    Keep,
+}
+
+/// A file that is written by the scaffold. All operations are planned before anything is written.
+#[derive(Debug)]
+struct FileOperation {
+    label: &'static str,
+    path: PathBuf,
+    contents: String,
+    action: Action,
+}
+
+impl FileOperation {
+    fn describe(&self, dry_run: bool) -> String {
+        let is_empty = self.contents.is_empty();
+        let verb = match (self.action, dry_run) {
+            (Action::Create, false) if is_empty => "Created empty",
+            (Action::Create, true) if is_empty => "Would create empty",
+            (Action::Create, false) => "Created",
+            (Action::Create, true) => "Would create",
+            (Action::Overwrite, false) if is_empty => "Truncated",
+            (Action::Overwrite, true) if is_empty => "Would truncate",
+            (Action::Overwrite, false) => "Overwrote",
+            (Action::Overwrite, true) => "Would overwrite",
+            (Action::Keep, false) => "Kept existing",
+            (Action::Keep, true) => "Would keep existing",
+        };
+        format!("{verb} {} file \"{}\"", self.label, self.path.display())
+    }
+}
+
+/// Plans the file operations for `day`. Existing modules are only replaced with `overwrite`.
+/// Inputs and examples that already have content are only emptied with `reset_data`.
+fn plan(
+    root: &Path,
+    day: Day,
+    options: &ScaffoldOptions,
+    module: String,
+) -> Result<Vec<FileOperation>, String> {
+    let module_path = root.join(format!("src/bin/{day}.rs"));
+
+    let module_action = match (module_path.exists(), options.overwrite) {
+        (false, _) => Action::Create,
+        (true, true) => Action::Overwrite,
+        (true, false) => {
+            return Err(format!(
+                "module file \"{}\" exists already. Use `--overwrite` to replace it.",
+                module_path.display()
+            ))
         }
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    let data_file = |label, path: PathBuf| {
+        let action = match fs::metadata(&path) {
+            Err(_) => Action::Create,
+            Ok(metadata) if metadata.len() > 0 && options.reset_data => Action::Overwrite,
+            Ok(_) => Action::Keep,
+        };
+        FileOperation {
+            label,
+            path,
+            contents: String::new(),
+            action,
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    };
+
+    Ok(vec![
+        FileOperation {
+            label: "module",
+            path: module_path,
+            contents: module,
+            action: module_action,
+        },
+        data_file("input", root.join(format!("data/inputs/{day}.txt"))),
+        data_file("example", root.join(format!("data/examples/{day}.txt"))),
+    ])
+}
+
+fn write(operation: &FileOperation) -> Result<(), std::io::Error> {
+    let mut file = OpenOptions::new();
+    match operation.action {
+        Action::Create => file.create_new(true),
+        Action::Overwrite => file.truncate(true),
+        Action::Keep => return Ok(()),
+    };
+    file.write(true)
+        .open(&operation.path)?
+        .write_all(operation.contents.as_bytes())
+}
+
+/// Applies all operations. If one of them fails, the files written before are restored.
+fn apply(operations: &[FileOperation]) -> Result<(), String> {
+    // previous contents of every written file, `None` if it was created.
+    let mut written: Vec<(&FileOperation, Option<Vec<u8>>)> = Vec::new();
+
+    for operation in operations {
+        let previous = match operation.action {
+            Action::Keep => continue,
+            Action::Create => None,
+            // without a backup, a rollback could not restore the file, so nothing is written.
+            Action::Overwrite => match fs::read(&operation.path) {
+                Ok(contents) => Some(contents),
+                Err(e) => {
+                    rollback(&written);
+                    return Err(format!(
+                        "failed to back up {} file \"{}\": {e}",
+                        operation.label,
+                        operation.path.display()
+                    ));
+                }
+            },
+        };
+
+        if let Err(e) = write(operation) {
+            rollback(&written);
+            return Err(format!(
+                "failed to write {} file \"{}\": {e}",
+                operation.label,
+                operation.path.display()
+            ));
         }
+
+        written.push((operation, previous));
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+    Ok(())
+}
+
+fn rollback(written: &[(&FileOperation, Option<Vec<u8>>)]) {
+    for (operation, previous) in written.iter().rev() {
+        let result = match previous {
+            Some(contents) => fs::write(&operation.path, contents),
+            None => fs::remove_file(&operation.path),
+        };
+
+        match result {
+            Ok(()) => eprintln!("Rolled back \"{}\"", operation.path.display()),
+            Err(e) => eprintln!("Failed to roll back \"{}\": {e}", operation.path.display()),
         }
+    }
+}
+
+pub fn handle(day: Day, options: &ScaffoldOptions) {
+    let template = match load_template(&options.template.name) {
+        Ok(template) => template,
         Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+            eprintln!("Failed to load template: {e}");
             process::exit(1);
         }
-    }
+    };
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
-        }
+    let title = read_title(day).unwrap_or_else(|| format!("Day {}", day.into_inner()));
+    let module = render_module(&template, day, &options.template, &title);
+
+    let operations = match plan(Path::new(""), day, options, module) {
+        Ok(operations) => operations,
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("Failed to scaffold day {day}: {e}");
             process::exit(1);
         }
+    };
+
+    if !options.dry_run {
+        if let Err(e) = apply(&operations) {
+            eprintln!("Failed to scaffold day {day}: {e}");
+            process::exit(1);
+        }
+    }
+
+    for operation in &operations {
+        println!("{}", operation.describe(options.dry_run));
+    }
+
+    if !options.dry_run {
+        println!("---");
+        println!("🎄 Type `cargo solve {day}` to run your solution.");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::{
+        apply, plan, render_module, Action, FileOperation, ScaffoldOptions, TemplateOptions,
+        MODULE_TEMPLATE,
+    };
+    use crate::day;
+
+    /// Creates an empty project layout in a temporary directory.
+    fn setup(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["src/bin", "data/inputs", "data/examples"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn plans_new_day() {
+        let root = setup("new");
+        let operations =
+            plan(&root, day!(1), &ScaffoldOptions::default(), "module".into()).unwrap();
+        assert!(operations.iter().all(|o| o.action == Action::Create));
+
+        apply(&operations).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/bin/01.rs")).unwrap(),
+            "module"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("data/inputs/01.txt")).unwrap(),
+            ""
+        );
+        fs::remove_dir_all(root).unwrap();
     }
 
-    println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    #[test]
+    fn refuses_existing_module() {
+        let root = setup("existing");
+        fs::write(root.join("src/bin/01.rs"), "solution").unwrap();
+        assert!(plan(&root, day!(1), &ScaffoldOptions::default(), "module".into()).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn keeps_non_empty_inputs() {
+        let root = setup("inputs");
+        fs::write(root.join("data/inputs/01.txt"), "1 2 3").unwrap();
+
+        let operations =
+            plan(&root, day!(1), &ScaffoldOptions::default(), "module".into()).unwrap();
+        assert_eq!(operations[1].action, Action::Keep);
+        apply(&operations).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("data/inputs/01.txt")).unwrap(),
+            "1 2 3"
+        );
+
+        let options = ScaffoldOptions {
+            overwrite: true,
+            ..Default::default()
+        };
+        let operations = plan(&root, day!(1), &options, "module".into()).unwrap();
+        assert_eq!(operations[0].action, Action::Overwrite);
+        assert_eq!(operations[1].action, Action::Keep);
+
+        let options = ScaffoldOptions {
+            reset_data: true,
+            ..options
+        };
+        let operations = plan(&root, day!(1), &options, "module".into()).unwrap();
+        assert_eq!(operations[1].action, Action::Overwrite);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rolls_back_on_failure() {
+        let root = setup("rollback");
+        fs::remove_dir_all(root.join("data/examples")).unwrap();
+
+        let operations =
+            plan(&root, day!(1), &ScaffoldOptions::default(), "module".into()).unwrap();
+        assert!(apply(&operations).is_err());
+        assert!(!root.join("src/bin/01.rs").exists());
+        assert!(!root.join("data/inputs/01.txt").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn aborts_without_backup() {
+        let root = setup("backup");
+        let operations = [
+            FileOperation {
+                label: "module",
+                path: root.join("src/bin/01.rs"),
+                contents: "module".into(),
+                action: Action::Create,
+            },
+            // a directory cannot be read as a file, so there is nothing to restore it from.
+            FileOperation {
+                label: "input",
+                path: root.join("data/inputs"),
+                contents: String::new(),
+                action: Action::Overwrite,
+            },
+        ];
+
+        let error = apply(&operations).unwrap_err();
+        assert!(error.contains("failed to back up input file"));
+        assert!(!root.join("src/bin/01.rs").exists());
+        assert!(root.join("data/inputs").is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn renders_default_template() {
+        let module = render_module(
+            MODULE_TEMPLATE,
+            day!(1),
+            &TemplateOptions::default(),
+            "Day 1",
+        );
+        assert!(module.starts_with("//! Day 1\nadvent_of_code::solution!(1);"));
+        assert!(module.contains("pub fn part_one(input: &str) -> Option<u32>"));
+        assert!(module.contains("assert_eq!(result, None);"));
+        assert!(!module.contains('%'));
+    }
+
+    #[test]
+    fn renders_types_and_answers() {
+        let options = TemplateOptions {
+            part_one_type: "u64".into(),
+            part_two_type: "String".into(),
+            example_part_one: Some("42".into()),
+            example_part_two: Some("6,1".into()),
+            ..Default::default()
+        };
+        let module = render_module(MODULE_TEMPLATE, day!(18), &options, "Day 18: RAM Run");
+        assert!(module.starts_with("//! Day 18: RAM Run\n"));
+        assert!(module.contains("pub fn part_one(input: &str) -> Option<u64>"));
+        assert!(module.contains("pub fn part_two(input: &str) -> Option<String>"));
+        assert!(module.contains("assert_eq!(result, Some(42));"));
+        assert!(module.contains("assert_eq!(result, Some(\"6,1\".to_string()));"));
+    }
 }
@@ -0,0 +1,49 @@
+use std::process;
+
+use crate::template::stars::Stars;
+use crate::template::{readme_stars, Day, Event, ANSI_BOLD, ANSI_RESET};
+
+pub fn handle(mark: Option<(Day, u8)>) {
+    if let Some((day, part)) = mark {
+        record(day, part);
+        println!();
+    }
+
+    let stars = Stars::read_from_file();
+
+    match Event::current().year() {
+        Some(year) => println!("{ANSI_BOLD}🎄 Advent of Code {year}{ANSI_RESET}"),
+        None => println!("{ANSI_BOLD}🎄 Advent of Code{ANSI_RESET}"),
+    }
+    println!();
+    println!("{}", stars.calendar());
+    println!();
+    println!("{ANSI_BOLD}Total:{ANSI_RESET} {}⭐", stars.total());
+}
+
+/// Records a star in the progress store and updates the readme.
+pub fn record(day: Day, part: u8) {
+    let mut stars = Stars::read_from_file();
+
+    match stars.mark(day, part) {
+        Ok(true) => {}
+        Ok(false) => {
+            println!("Star for day {day} part {part} was already recorded.");
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+
+    if let Err(e) = stars.store_file() {
+        eprintln!("Failed to store stars: {e}");
+        process::exit(1);
+    }
+    println!("⭐ Recorded star for day {day} part {part}.");
+
+    if readme_stars::update(&stars).is_err() {
+        eprintln!("Failed to update stars in the readme.");
+    }
+}
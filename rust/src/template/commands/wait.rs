@@ -0,0 +1,126 @@
+use std::{
+    io::{stdout, Write},
+    path::Path,
+    process, thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::template::{
+    aoc_cli,
+    commands::{read, scaffold},
+    now, Day, Event, ANSI_BOLD, ANSI_RESET,
+};
+
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 8;
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Waits for the next puzzle to unlock, then scaffolds, downloads and reads it.
+pub fn handle() {
+    // check early, so we don't find out hours later at release time.
+    if aoc_cli::check().is_err() {
+        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        process::exit(1);
+    }
+
+    let Some((day, unlock)) = Day::next_unlock(now()) else {
+        match Event::current().year() {
+            Some(year) => eprintln!(
+                "Advent of Code {year} is over. Set `AOC_YEAR` to an upcoming event to wait for it."
+            ),
+            None => eprintln!("Could not determine when the next puzzle unlocks."),
+        }
+        process::exit(1);
+    };
+
+    countdown(day, unlock);
+
+    if Path::new(&format!("src/bin/{day}.rs")).exists() {
+        println!("Module for day {day} exists already, skipping scaffold.");
+    } else {
+        scaffold::handle(day, &Default::default());
+    }
+
+    download_with_retry(day);
+    read::handle(day, false);
+}
+
+fn countdown(day: Day, unlock: DateTime<Utc>) {
+    let mut stdout = stdout();
+
+    loop {
+        let remaining = unlock - now();
+        if remaining <= TimeDelta::zero() {
+            break;
+        }
+
+        print!(
+            "\r⏳ Day {day} unlocks in {ANSI_BOLD}{}{ANSI_RESET} ",
+            format_countdown(remaining)
+        );
+        let _ = stdout.flush();
+
+        let sleep = remaining.to_std().unwrap_or_default();
+        thread::sleep(sleep.min(Duration::from_secs(1)));
+    }
+
+    println!("\r🎄 Day {day} is unlocked!{}", " ".repeat(24));
+}
+
+fn format_countdown(remaining: TimeDelta) -> String {
+    let seconds = remaining.num_seconds();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
+
+    if days > 0 {
+        format!("{days}d {hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+/// The puzzle might not be available right at the unlock instant, so failed downloads are retried
+/// with an exponential backoff.
+fn download_with_retry(day: Day) {
+    let mut delay = Duration::from_secs(1);
+
+    for attempt in 1..=MAX_DOWNLOAD_ATTEMPTS {
+        match aoc_cli::download(day) {
+            Ok(_) => return,
+            Err(e) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                eprintln!("Download attempt {attempt} failed: {e} Retrying in {delay:?}...");
+                thread::sleep(delay);
+                delay = (delay * 2).min(MAX_BACKOFF);
+            }
+            Err(e) => {
+                eprintln!("failed to call aoc-cli: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::format_countdown;
+
+    #[test]
+    fn formats_countdown() {
+        assert_eq!(format_countdown(TimeDelta::seconds(59)), "00:00:59");
+        assert_eq!(
+            format_countdown(TimeDelta::seconds(3 * 3600 + 61)),
+            "03:01:01"
+        );
+        assert_eq!(
+            format_countdown(TimeDelta::seconds(2 * 86_400 + 3600)),
+            "2d 01:00:00"
+        );
+    }
+}
@@ -3,12 +3,17 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 #[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, TimeDelta, TimeZone, Utc};
+
+use crate::template::Event;
 
 #[cfg(feature = "today")]
 const SERVER_UTC_OFFSET: i32 = -5;
 
-/// A valid day number of advent (i.e. an integer in range 1 to 25).
+#[cfg(feature = "today")]
+const CLOCK_OFFSET_ENV: &str = "AOC_CLOCK_OFFSET";
+
+/// A valid day number of advent (i.e. an integer in range 1 to the last day of the [`Event`]).
 ///
 /// # Display
 /// This value displays as a two digit number.
@@ -22,10 +27,10 @@ const SERVER_UTC_OFFSET: i32 = -5;
 pub struct Day(u8);
 
 impl Day {
-    /// Creates a [`Day`] from the provided value if it's in the valid range,
+    /// Creates a [`Day`] from the provided value if it's part of the current [`Event`],
     /// returns [`None`] otherwise.
     pub fn new(day: u8) -> Option<Self> {
-        if day == 0 || day > 25 {
+        if !Event::current().contains(day) {
             return None;
         }
         Some(Self(day))
@@ -41,20 +46,72 @@ impl Day {
     pub fn into_inner(self) -> u8 {
         self.0
     }
+
+    /// The number of parts of this day's puzzle.
+    pub fn parts(self) -> u8 {
+        Event::current().parts(self.0)
+    }
 }
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
+    /// Returns the current day if it's part of the event calendar in december, `None` otherwise.
+    /// If a year is configured, only days of that year's event count.
     pub fn today() -> Option<Self> {
         let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
+        let today = now().with_timezone(&offset);
+        let event = Event::current();
+        let is_event_year = event
+            .year()
+            .is_none_or(|year| i32::from(year) == today.year());
+        if is_event_year && today.month() == 12 && today.day() <= u32::from(event.last_day()) {
             Self::new(u8::try_from(today.day()).ok()?)
         } else {
             None
         }
     }
+
+    /// Returns the next day that unlocks after `now`, together with the instant of its release.
+    /// Puzzles unlock at midnight server time, on every day of the event calendar in december.
+    ///
+    /// If a year is configured, only that year's event unlocks, so there is no next day once it
+    /// is over.
+    pub fn next_unlock(now: DateTime<Utc>) -> Option<(Self, DateTime<Utc>)> {
+        Self::next_unlock_of(Event::current(), now)
+    }
+
+    fn next_unlock_of(event: Event, now: DateTime<Utc>) -> Option<(Self, DateTime<Utc>)> {
+        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+        let today = now.with_timezone(&offset);
+        let last_day = u32::from(event.last_day());
+
+        let (year, day) = match (today.month(), today.day()) {
+            (12, day) if day < last_day => (today.year(), day + 1),
+            (12, _) => (today.year() + 1, 1),
+            _ => (today.year(), 1),
+        };
+        let (year, day) = match event.year().map(i32::from) {
+            None => (year, day),
+            Some(event_year) if event_year == year => (year, day),
+            Some(event_year) if event_year > year => (event_year, 1),
+            Some(_) => return None,
+        };
+
+        let unlock = offset.with_ymd_and_hms(year, 12, day, 0, 0, 0).single()?;
+        Some((Self(u8::try_from(day).ok()?), unlock.to_utc()))
+    }
+}
+
+/// Returns the current time, shifted by the amount of seconds in `AOC_CLOCK_OFFSET` if set.
+/// This allows testing `today` and its `--wait` mode outside of december.
+#[cfg(feature = "today")]
+pub fn now() -> DateTime<Utc> {
+    let offset = std::env::var(CLOCK_OFFSET_ENV)
+        .ok()
+        .and_then(|x| x.trim().parse().ok())
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or_default();
+    Utc::now() + offset
 }
 
 impl Display for Day {
@@ -94,26 +151,39 @@ impl Error for DayFromStrError {}
 
 impl Display for DayFromStrError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("expecting a day number between 1 and 25")
+        write!(
+            f,
+            "expecting a day number between 1 and {}",
+            Event::current().last_day()
+        )
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of advent from the 1st to the last day of the [`Event`].
 pub fn all_days() -> AllDays {
     AllDays::new()
 }
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of advent from the 1st to the last day of the [`Event`].
 pub struct AllDays {
     current: u8,
+    last: u8,
 }
 
 impl AllDays {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { current: 1 }
+        Self::for_event(Event::current())
+    }
+
+    /// Yields every day of the provided [`Event`].
+    pub fn for_event(event: Event) -> Self {
+        Self {
+            current: 1,
+            last: event.last_day(),
+        }
     }
 }
 
@@ -121,10 +191,10 @@ impl Iterator for AllDays {
     type Item = Day;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 25 {
+        if self.current > self.last {
             return None;
         }
-        // NOTE: the iterator starts at 1 and we have verified that the value is not above 25.
+        // NOTE: the iterator starts at 1 and we have verified that the value is not above the last day.
         let day = Day(self.current);
         self.current += 1;
 
@@ -139,11 +209,11 @@ impl Iterator for AllDays {
 macro_rules! day {
     ($day:expr) => {{
         const _ASSERT: () = assert!(
-            $day != 0 && $day <= 25,
+            $crate::template::Event::current().contains($day),
             concat!(
                 "invalid day number `",
                 $day,
-                "`, expecting a value between 1 and 25"
+                "`, expecting a day of the configured event"
             ),
         );
         $crate::template::Day::__new_unchecked($day)
@@ -154,7 +224,8 @@ macro_rules! day {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{all_days, Day};
+    use super::{all_days, AllDays, Day};
+    use crate::template::Event;
 
     #[test]
     fn all_days_iterator() {
@@ -187,6 +258,69 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn all_days_iterator_for_short_event() {
+        let days: Vec<Day> = AllDays::for_event(Event::for_year(2025)).collect();
+        assert_eq!(days.len(), 12);
+        assert_eq!(days.last(), Some(&Day(12)));
+    }
+
+    #[cfg(feature = "today")]
+    #[test]
+    fn next_unlock_during_event() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 12, 5, 12, 0, 0).unwrap();
+        let (day, unlock) = Day::next_unlock(now).unwrap();
+        assert_eq!(day, Day(6));
+        assert_eq!(unlock, Utc.with_ymd_and_hms(2024, 12, 6, 5, 0, 0).unwrap());
+    }
+
+    #[cfg(feature = "today")]
+    #[test]
+    fn next_unlock_before_and_after_event() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 11, 30, 12, 0, 0).unwrap();
+        let (day, unlock) = Day::next_unlock(now).unwrap();
+        assert_eq!(day, Day(1));
+        assert_eq!(unlock, Utc.with_ymd_and_hms(2024, 12, 1, 5, 0, 0).unwrap());
+
+        // 2am UTC on the 25th is still the 24th in server time.
+        let now = Utc.with_ymd_and_hms(2024, 12, 25, 2, 0, 0).unwrap();
+        let (day, unlock) = Day::next_unlock_of(Event::for_year(2024), now).unwrap();
+        assert_eq!(day, Day(25));
+        assert_eq!(unlock, Utc.with_ymd_and_hms(2024, 12, 25, 5, 0, 0).unwrap());
+
+        // the configured event is over, the next one is not.
+        let now = Utc.with_ymd_and_hms(2024, 12, 25, 12, 0, 0).unwrap();
+        assert_eq!(Day::next_unlock_of(Event::for_year(2024), now), None);
+        let now = Utc.with_ymd_and_hms(2025, 12, 5, 12, 0, 0).unwrap();
+        assert_eq!(Day::next_unlock_of(Event::for_year(2024), now), None);
+        let (day, unlock) = Day::next_unlock_of(Event::for_year(2025), now).unwrap();
+        assert_eq!(day, Day(6));
+        assert_eq!(unlock, Utc.with_ymd_and_hms(2025, 12, 6, 5, 0, 0).unwrap());
+    }
+
+    #[cfg(feature = "today")]
+    #[test]
+    fn next_unlock_without_configured_year() {
+        use chrono::{TimeZone, Utc};
+
+        // without a year, the unlocks continue with the next event.
+        let now = Utc.with_ymd_and_hms(2024, 12, 26, 2, 0, 0).unwrap();
+        let (day, unlock) = Day::next_unlock_of(Event::classic(), now).unwrap();
+        assert_eq!(day, Day(1));
+        assert_eq!(unlock, Utc.with_ymd_and_hms(2025, 12, 1, 5, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn rejects_days_outside_of_event() {
+        assert_eq!(Day::new(0), None);
+        assert_eq!(Day::new(25), Some(Day(25)));
+        assert_eq!(Day::new(26), None);
+    }
 }
 
 /* -------------------------------------------------------------------------- */
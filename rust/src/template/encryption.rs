@@ -0,0 +1,232 @@
+/// Module that stores puzzle inputs encrypted at rest, so they can be committed to public repositories.
+///
+/// Encrypted files live next to their plaintext counterparts with an additional `.enc` extension,
+/// e.g. `data/inputs/01.txt.enc`. The key is read from the `AOC_INPUT_KEY` environment variable,
+/// from the file referenced by `AOC_INPUT_KEY_FILE` or from `.aoc_input_key` in the project root.
+use std::{
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use sha2::{Digest, Sha256};
+
+use crate::template::Day;
+
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+const KEY_ENV: &str = "AOC_INPUT_KEY";
+const KEY_FILE_ENV: &str = "AOC_INPUT_KEY_FILE";
+const DEFAULT_KEY_FILE: &str = ".aoc_input_key";
+
+/// Identifies files written by this module and the version of their layout.
+const MAGIC: &[u8; 8] = b"AOCENC01";
+const NONCE_LEN: usize = 24;
+
+#[derive(Debug)]
+pub enum EncryptionError {
+    MissingKey,
+    InvalidFile,
+    Decryption,
+    IO(io::Error),
+}
+
+impl Display for EncryptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncryptionError::MissingKey => write!(
+                f,
+                "no input key found. Set `{KEY_ENV}`, `{KEY_FILE_ENV}` or create `{DEFAULT_KEY_FILE}`."
+            ),
+            EncryptionError::InvalidFile => write!(f, "file is not an encrypted input."),
+            EncryptionError::Decryption => {
+                write!(f, "could not decrypt file, the input key is probably wrong.")
+            }
+            EncryptionError::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<io::Error> for EncryptionError {
+    fn from(e: io::Error) -> Self {
+        EncryptionError::IO(e)
+    }
+}
+
+/// The secret shared by everyone who is allowed to read the inputs.
+pub struct InputKey([u8; 32]);
+
+impl InputKey {
+    /// Derives a key from an arbitrary secret. Use a long random value, e.g. `openssl rand -hex 32`.
+    pub fn from_secret(secret: &str) -> Self {
+        Self(Sha256::digest(secret.trim().as_bytes()).into())
+    }
+
+    /// Reads the key from the environment or from a key file. A blank secret, e.g. from a CI
+    /// secret that is not set, counts as missing.
+    pub fn load() -> Result<Self, EncryptionError> {
+        Self::load_from(|name| env::var(name).ok())
+    }
+
+    /// Like [`InputKey::load`], but looks up the variables with `var` instead of in the
+    /// environment of the process.
+    pub fn load_from(var: impl Fn(&str) -> Option<String>) -> Result<Self, EncryptionError> {
+        if let Some(secret) = var(KEY_ENV).filter(|secret| is_set(secret)) {
+            return Ok(Self::from_secret(&secret));
+        }
+
+        let key_file = var(KEY_FILE_ENV).unwrap_or_else(|| DEFAULT_KEY_FILE.into());
+        match fs::read_to_string(key_file) {
+            Ok(secret) if is_set(&secret) => Ok(Self::from_secret(&secret)),
+            _ => Err(EncryptionError::MissingKey),
+        }
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+
+    /// The nonce is derived from the key and the plaintext, so that encrypting an unchanged file
+    /// produces the same output and does not show up as a change in git.
+    fn nonce_for(&self, plaintext: &[u8]) -> XNonce {
+        let digest = Sha256::new()
+            .chain_update(self.0)
+            .chain_update(plaintext)
+            .finalize();
+        *XNonce::from_slice(&digest[..NONCE_LEN])
+    }
+}
+
+fn is_set(secret: &str) -> bool {
+    !secret.trim().is_empty()
+}
+
+/// Files of a day that must not be committed in plain text.
+pub fn private_files(day: Day) -> [PathBuf; 3] {
+    [
+        PathBuf::from(format!("data/inputs/{day}.txt")),
+        PathBuf::from(format!("data/puzzles/{day}.md")),
+        PathBuf::from(format!("data/answers/{day}.txt")),
+    ]
+}
+
+/// Returns the path of the encrypted counterpart of `path`, e.g. `01.txt` -> `01.txt.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut file_name = path.as_os_str().to_owned();
+    file_name.push(".");
+    file_name.push(ENCRYPTED_EXTENSION);
+    PathBuf::from(file_name)
+}
+
+pub fn encrypt(plaintext: &[u8], key: &InputKey) -> Vec<u8> {
+    let nonce = key.nonce_for(plaintext);
+    // NOTE: encryption only fails if the plaintext exceeds the cipher's size limit of 256GiB.
+    let ciphertext = key.cipher().encrypt(&nonce, plaintext).unwrap();
+
+    let mut output = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&nonce);
+    output.extend(ciphertext);
+    output
+}
+
+pub fn decrypt(data: &[u8], key: &InputKey) -> Result<Vec<u8>, EncryptionError> {
+    let data = data
+        .strip_prefix(MAGIC)
+        .ok_or(EncryptionError::InvalidFile)?;
+    if data.len() < NONCE_LEN {
+        return Err(EncryptionError::InvalidFile);
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    key.cipher()
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EncryptionError::Decryption)
+}
+
+/// Encrypts the file at `path` and writes the result to its encrypted counterpart.
+pub fn encrypt_file(path: &Path, key: &InputKey) -> Result<PathBuf, EncryptionError> {
+    let target = encrypted_path(path);
+    fs::write(&target, encrypt(&fs::read(path)?, key))?;
+    Ok(target)
+}
+
+/// Decrypts the encrypted counterpart of `path` and writes the plaintext to `path`.
+pub fn decrypt_file(path: &Path, key: &InputKey) -> Result<(), EncryptionError> {
+    let plaintext = decrypt(&fs::read(encrypted_path(path))?, key)?;
+    fs::write(path, plaintext)?;
+    Ok(())
+}
+
+/// Reads the encrypted counterpart of `path` to a string.
+pub fn read_to_string(path: &Path) -> Result<String, EncryptionError> {
+    let key = InputKey::load()?;
+    let plaintext = decrypt(&fs::read(encrypted_path(path))?, &key)?;
+    String::from_utf8(plaintext).map_err(|_| EncryptionError::InvalidFile)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::Path;
+
+    use super::{
+        decrypt, encrypt, encrypted_path, EncryptionError, InputKey, KEY_ENV, KEY_FILE_ENV,
+    };
+
+    #[test]
+    fn roundtrips_plaintext() {
+        let key = InputKey::from_secret("secret");
+        let ciphertext = encrypt(b"1,2,3\n4,5,6", &key);
+        assert_eq!(decrypt(&ciphertext, &key).unwrap(), b"1,2,3\n4,5,6");
+    }
+
+    #[test]
+    fn encrypts_deterministically() {
+        let key = InputKey::from_secret("secret");
+        assert_eq!(encrypt(b"input", &key), encrypt(b"input", &key));
+        assert_ne!(encrypt(b"input", &key), encrypt(b"other input", &key));
+    }
+
+    #[test]
+    fn rejects_wrong_key() {
+        let ciphertext = encrypt(b"input", &InputKey::from_secret("secret"));
+        let result = decrypt(&ciphertext, &InputKey::from_secret("not the secret"));
+        assert!(matches!(result, Err(EncryptionError::Decryption)));
+    }
+
+    #[test]
+    fn rejects_unknown_files() {
+        let result = decrypt(b"plain text", &InputKey::from_secret("secret"));
+        assert!(matches!(result, Err(EncryptionError::InvalidFile)));
+    }
+
+    #[test]
+    fn treats_blank_keys_as_missing() {
+        let result = InputKey::load_from(|name| match name {
+            KEY_ENV => Some("  ".into()),
+            KEY_FILE_ENV => Some("target/no such key file".into()),
+            _ => None,
+        });
+        assert!(matches!(result, Err(EncryptionError::MissingKey)));
+    }
+
+    #[test]
+    fn loads_keys_from_variables() {
+        let result = InputKey::load_from(|name| (name == KEY_ENV).then(|| "secret".into()));
+        let ciphertext = encrypt(b"input", &InputKey::from_secret("secret"));
+        assert_eq!(decrypt(&ciphertext, &result.unwrap()).unwrap(), b"input");
+    }
+
+    #[test]
+    fn appends_extension() {
+        let path = encrypted_path(Path::new("data/inputs/01.txt"));
+        assert_eq!(path, Path::new("data/inputs/01.txt.enc"));
+    }
+}
@@ -0,0 +1,166 @@
+/// Renders the puzzle descriptions written by aoc-cli for display in a terminal.
+/// This only covers the subset of markdown (and leftover html) that appears in puzzle descriptions.
+use crate::template::{ANSI_BOLD, ANSI_GREEN, ANSI_RESET, ANSI_UNDERLINE, ANSI_YELLOW};
+
+const ANSI_CODE: &str = "\x1b[36m";
+
+/// Renders `markdown` with ANSI styling for headings, emphasized text and code.
+pub fn render(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block || line.starts_with("    ") || line.starts_with('\t') {
+            let code = line.strip_prefix("    ").unwrap_or(line);
+            lines.push(format!("    {ANSI_CODE}{}{ANSI_RESET}", render_code(code)));
+        } else if let Some(heading) = parse_heading(line) {
+            lines.push(format!(
+                "{ANSI_BOLD}{ANSI_GREEN}{ANSI_UNDERLINE}{}{ANSI_RESET}",
+                unescape(heading)
+            ));
+        } else if is_rule(line) {
+            // aoc-cli underlines headings with a rule, which is redundant with the styling above.
+            continue;
+        } else {
+            lines.push(render_inline(line));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn parse_heading(line: &str) -> Option<&str> {
+    let heading = line.trim_start_matches('#');
+    if heading.len() == line.len() || !heading.starts_with(' ') {
+        return None;
+    }
+    Some(heading.trim())
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3 && line.chars().all(|c| c == '-' || c == '=')
+}
+
+/// Removes markdown escapes, e.g. `\---` -> `---`.
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(next)) if next.is_ascii_punctuation() => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Code is printed verbatim, except for `<em>` tags, which mark answers within examples.
+fn render_code(code: &str) -> String {
+    code.replace("<em>", &format!("{ANSI_BOLD}{ANSI_YELLOW}"))
+        .replace("</em>", &format!("{ANSI_RESET}{ANSI_CODE}"))
+}
+
+fn render_inline(line: &str) -> String {
+    let line = line
+        .replace("<em>", "*")
+        .replace("</em>", "*")
+        .replace("**", "*");
+
+    let mut result = String::with_capacity(line.len());
+    let mut is_emphasized = false;
+    let mut is_code = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !is_code && chars.peek().is_some_and(char::is_ascii_punctuation) => {
+                result.push(chars.next().unwrap());
+            }
+            '`' => {
+                is_code = !is_code;
+                result.push_str(if is_code { ANSI_CODE } else { ANSI_RESET });
+                if !is_code && is_emphasized {
+                    result.push_str(ANSI_BOLD);
+                    result.push_str(ANSI_YELLOW);
+                }
+            }
+            // within code, emphasis only counts if it wraps the whole span, e.g. `*42*`.
+            '*' if !is_code || chars.peek() == Some(&'`') || result.ends_with(ANSI_CODE) => {
+                is_emphasized = !is_emphasized;
+                if is_emphasized {
+                    result.push_str(ANSI_BOLD);
+                    result.push_str(ANSI_YELLOW);
+                } else {
+                    result.push_str(ANSI_RESET);
+                    if is_code {
+                        result.push_str(ANSI_CODE);
+                    }
+                }
+            }
+            c => result.push(c),
+        }
+    }
+
+    if is_code || is_emphasized {
+        result.push_str(ANSI_RESET);
+    }
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, ANSI_CODE};
+    use crate::template::{ANSI_BOLD, ANSI_GREEN, ANSI_RESET, ANSI_UNDERLINE, ANSI_YELLOW};
+
+    #[test]
+    fn renders_headings() {
+        let rendered = render("## \\--- Day 1: Historian Hysteria ---\n----------");
+        assert_eq!(
+            rendered,
+            format!("{ANSI_BOLD}{ANSI_GREEN}{ANSI_UNDERLINE}--- Day 1: Historian Hysteria ---{ANSI_RESET}")
+        );
+    }
+
+    #[test]
+    fn renders_emphasis() {
+        let rendered = render("The answer is *42*, not <em>43</em>.");
+        assert_eq!(
+            rendered,
+            format!("The answer is {ANSI_BOLD}{ANSI_YELLOW}42{ANSI_RESET}, not {ANSI_BOLD}{ANSI_YELLOW}43{ANSI_RESET}.")
+        );
+    }
+
+    #[test]
+    fn renders_code() {
+        let rendered = render("Compute `2*3*4` first.");
+        assert_eq!(
+            rendered,
+            format!("Compute {ANSI_CODE}2*3*4{ANSI_RESET} first.")
+        );
+    }
+
+    #[test]
+    fn renders_code_blocks() {
+        let rendered = render("```\n3   4\n<em>4</em>   3\n```");
+        assert_eq!(
+            rendered,
+            format!(
+                "    {ANSI_CODE}3   4{ANSI_RESET}\n    {ANSI_CODE}{ANSI_BOLD}{ANSI_YELLOW}4{ANSI_RESET}{ANSI_CODE}   3{ANSI_RESET}"
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_plain_text() {
+        assert_eq!(render("Just text."), "Just text.");
+    }
+}
@@ -1,18 +1,27 @@
-use std::{env, fs};
+use std::{env, fs, path::Path};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod encryption;
 pub mod runner;
 
+pub use calendar::*;
 pub use day::*;
 
+mod calendar;
 mod day;
+mod markdown;
 mod readme_benchmarks;
+mod readme_stars;
 mod run_multi;
+pub mod stars;
 mod timings;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
+pub const ANSI_UNDERLINE: &str = "\x1b[4m";
+pub const ANSI_GREEN: &str = "\x1b[32m";
+pub const ANSI_YELLOW: &str = "\x1b[33m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
 /// Helper function that reads a text file to a string.
@@ -20,8 +29,7 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    read_or_decrypt(&filepath)
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
@@ -32,6 +40,34 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
         .join("data")
         .join(folder)
         .join(format!("{day}-{part}.txt"));
+    read_or_decrypt(&filepath)
+}
+
+/// Reads the input of `day` together with its recorded answers in `data/answers`, one line per
+/// part, if both are available. Encrypted files are skipped if there is no key to read them.
+#[must_use]
+pub fn read_recorded(day: Day) -> Option<(String, String)> {
+    let cwd = env::current_dir().unwrap();
+    let path = |folder| cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let (input, answers) = (path("inputs"), path("answers"));
+
+    let exists = |path: &Path| path.exists() || encryption::encrypted_path(path).exists();
+    if !exists(&input) || !exists(&answers) {
+        return None;
+    }
+    let is_encrypted = !input.exists() || !answers.exists();
+    if is_encrypted && encryption::InputKey::load().is_err() {
+        return None;
+    }
+    Some((read_or_decrypt(&input), read_or_decrypt(&answers)))
+}
+
+/// Reads a plain text file if present and falls back to its encrypted counterpart otherwise.
+fn read_or_decrypt(filepath: &Path) -> String {
+    if !filepath.exists() && encryption::encrypted_path(filepath).exists() {
+        return encryption::read_to_string(filepath)
+            .unwrap_or_else(|e| panic!("could not decrypt input file: {e}"));
+    }
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -64,5 +100,15 @@ macro_rules! solution {
             let input = $crate::template::read_file("inputs", DAY);
             $( run_part($func, &input, DAY, $part); )*
         }
+
+        /// Solves the real input and compares the results with the recorded answers, if both
+        /// are available.
+        #[cfg(test)]
+        #[test]
+        fn matches_recorded_answers() {
+            if let Some((input, answers)) = $crate::template::read_recorded(DAY) {
+                $( $crate::template::runner::check_answer($func, &input, &answers, DAY, $part); )*
+            }
+        }
     };
 }
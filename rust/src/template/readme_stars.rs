@@ -0,0 +1,127 @@
+/// Module that updates the readme with the star progress.
+/// The section is maintained the same way as the benchmarking table.
+use std::fs;
+
+use crate::template::readme_benchmarks::{get_path_for_bin, locate_table, Error};
+use crate::template::stars::Stars;
+use crate::template::{all_days, Event};
+
+static MARKER: &str = "<!--- stars table --->";
+
+fn render_star(is_solved: bool) -> &'static str {
+    if is_solved {
+        "⭐"
+    } else {
+        "-"
+    }
+}
+
+fn construct_table(prefix: &str, stars: &Stars) -> String {
+    let header = match Event::current().year() {
+        Some(year) => format!("{prefix} Stars {year}"),
+        None => format!("{prefix} Stars"),
+    };
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---:  |".into(),
+    ];
+
+    for day in all_days() {
+        let Some(day_stars) = stars.get(day) else {
+            continue;
+        };
+
+        let part_2 = if day.parts() == 1 {
+            ""
+        } else {
+            render_star(day_stars.part_2)
+        };
+
+        lines.push(format!(
+            "| [Day {}]({}) | {} | {} |",
+            day.into_inner(),
+            get_path_for_bin(day),
+            render_star(day_stars.part_1),
+            part_2
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(format!("**Total: {}⭐**", stars.total()));
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, stars: &Stars) -> Result<(), Error> {
+    let positions = locate_table(s, MARKER)?;
+    let table = construct_table("##", stars);
+    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    Ok(())
+}
+
+pub fn update(stars: &Stars) -> Result<(), Error> {
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, stars)?;
+    fs::write(path, &readme)?;
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, MARKER};
+    use crate::{day, template::stars::Stars};
+
+    fn get_mock_stars() -> Stars {
+        let mut stars = Stars::default();
+        stars.mark(day!(1), 1).unwrap();
+        stars.mark(day!(1), 2).unwrap();
+        stars.mark(day!(2), 1).unwrap();
+        stars.mark(day!(25), 1).unwrap();
+        stars
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let mut s = "# readme".to_string();
+        update_content(&mut s, &get_mock_stars()).unwrap();
+    }
+
+    #[test]
+    fn updates_existing_stars() {
+        let mut s = format!("foo\n{}{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, &get_mock_stars()).unwrap();
+        update_content(&mut s, &get_mock_stars()).unwrap();
+        assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
+        assert_eq!(s.matches("## Stars").collect::<Vec<&str>>().len(), 1);
+    }
+
+    #[test]
+    fn format_stars() {
+        let mut s = format!("foo\n{}\n{}\nbaz", MARKER, MARKER);
+        update_content(&mut s, &get_mock_stars()).unwrap();
+        let expected = [
+            "foo",
+            "<!--- stars table --->",
+            "## Stars 2024",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---:  |",
+            "| [Day 1](./src/bin/01.rs) | ⭐ | ⭐ |",
+            "| [Day 2](./src/bin/02.rs) | ⭐ | - |",
+            "| [Day 25](./src/bin/25.rs) | ⭐ |  |",
+            "",
+            "**Total: 4⭐**",
+            "<!--- stars table --->",
+            "baz",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+}
@@ -1,4 +1,5 @@
 /// Encapsulates code that interacts with solution functions.
+use std::cell::Cell;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
@@ -7,16 +8,70 @@ use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, commands::stars, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::{error, memo};
+
+/// Asserts that `func` solves `input` with the answer recorded for `part` in `answers`.
+/// Parts without a recorded answer are not checked.
+///
+/// # Panics
+///
+/// Panics if the answer differs from the recorded one.
+pub fn check_answer<T: Display>(
+    func: impl Fn(&str) -> Option<T>,
+    input: &str,
+    answers: &str,
+    day: Day,
+    part: u8,
+) {
+    let recorded = answers
+        .lines()
+        .nth(usize::from(part) - 1)
+        .map(str::trim)
+        .filter(|answer| !answer.is_empty());
+    if let Some(recorded) = recorded {
+        let answer = func(input).map(|answer| answer.to_string());
+        assert_eq!(
+            answer.as_deref(),
+            Some(recorded),
+            "day {day} part {part} differs from the recorded answer"
+        );
+    }
+}
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+pub fn run_part<I: Copy + AsRef<str>, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    // only the stats of the first run are reported, benchmark runs would add up.
+    memo::take_stats();
+    let cache_stats = Cell::new(None);
+
+    let run = error::capture(|| {
+        run_timed(&func, input, |result| {
+            cache_stats.set(memo::take_stats());
+            print_result(result, &part_str, "");
+        })
+    });
+    let (result, duration, samples) = match run {
+        Ok(run) => run,
+        Err(e) => {
+            print_result::<T>(&None, &part_str, " ");
+            eprintln!("{}", e.render(input.as_ref()));
+            process::exit(1);
+        }
+    };
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
+    if let Some(stats) = cache_stats.get() {
+        println!(" └ {ANSI_ITALIC}cache: {stats}{ANSI_RESET}");
+    }
+
     if let Some(result) = result {
         submit_result(result, day, part);
     }
@@ -161,5 +216,11 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let output = aoc_cli::submit(day, part, &result.to_string());
+
+    if matches!(&output, Ok(output) if aoc_cli::is_correct_answer(output)) {
+        stars::record(day, part);
+    }
+
+    Some(output)
 }
@@ -0,0 +1,291 @@
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{all_days, Day};
+
+static STARS_FILE_PATH: &str = "./data/stars.json";
+
+/// Represents the stars earned for a single day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayStars {
+    pub day: Day,
+    pub part_1: bool,
+    pub part_2: bool,
+}
+
+impl DayStars {
+    /// Number of stars earned for this day.
+    pub fn count(&self) -> u8 {
+        u8::from(self.part_1) + u8::from(self.part_2)
+    }
+}
+
+/// Represents the star progress of the event.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Stars {
+    pub data: Vec<DayStars>,
+}
+
+impl Stars {
+    /// Dehydrate stars to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(STARS_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate stars from a JSON file. If not present, returns no stars.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(STARS_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(Stars::try_from)
+            .unwrap_or_default()
+    }
+
+    /// Records the star for `part` of `day`. Returns `false` if it was already recorded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `day` has no such part.
+    pub fn mark(&mut self, day: Day, part: u8) -> Result<bool, String> {
+        if part == 0 || part > day.parts() {
+            return Err(format!(
+                "Day {day} has {} part(s), cannot mark part {part}.",
+                day.parts()
+            ));
+        }
+
+        let index = match self.data.iter().position(|s| s.day == day) {
+            Some(index) => index,
+            None => {
+                self.data.push(DayStars {
+                    day,
+                    part_1: false,
+                    part_2: false,
+                });
+                self.data.sort_unstable_by_key(|s| s.day);
+                self.data.iter().position(|s| s.day == day).unwrap()
+            }
+        };
+
+        let star = match part {
+            1 => &mut self.data[index].part_1,
+            _ => &mut self.data[index].part_2,
+        };
+
+        let is_new = !*star;
+        *star = true;
+        Ok(is_new)
+    }
+
+    /// Returns the stars of `day`, if any were earned.
+    pub fn get(&self, day: Day) -> Option<&DayStars> {
+        self.data.iter().find(|s| s.day == day)
+    }
+
+    /// Sum up all earned stars.
+    pub fn total(&self) -> u32 {
+        self.data.iter().map(|s| u32::from(s.count())).sum()
+    }
+
+    /// Checks whether every part of `day` has been solved.
+    pub fn is_day_complete(&self, day: Day) -> bool {
+        self.get(day).is_some_and(|s| s.count() == day.parts())
+    }
+
+    /// Renders the progress as a calendar with one ⭐ per solved part.
+    pub fn calendar(&self) -> String {
+        all_days()
+            .map(|day| {
+                let stars = self.get(day).map_or(0, DayStars::count);
+                // a stored file may hold a second star for a single part day.
+                let missing = day.parts().saturating_sub(stars);
+                format!(
+                    "{day} {}{}{}",
+                    "⭐".repeat(stars.into()),
+                    "☆".repeat(missing.into()),
+                    // single part days are padded to align with the other columns.
+                    " ".repeat((2 - day.parts()).into())
+                )
+            })
+            .collect::<Vec<_>>()
+            .chunks(5)
+            .map(|row| row.join("   "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<Stars> for JsonValue {
+    fn from(value: Stars) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for Stars {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(Stars {
+            data: json_data
+                .iter()
+                .map(DayStars::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&DayStars> for JsonValue {
+    fn from(value: &DayStars) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("part_1".into(), JsonValue::Boolean(value.part_1));
+        map.insert("part_2".into(), JsonValue::Boolean(value.part_2));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for DayStars {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected stars to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected stars.day to be a Day struct.")?;
+
+        let part_1 = json
+            .get("part_1")
+            .and_then(|v| v.get::<bool>().copied())
+            .ok_or("Expected stars.part_1 to be a boolean.")?;
+
+        let part_2 = json
+            .get("part_2")
+            .and_then(|v| v.get::<bool>().copied())
+            .ok_or("Expected stars.part_2 to be a boolean.")?;
+
+        Ok(DayStars {
+            day,
+            part_1,
+            part_2,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::day;
+
+    use super::{DayStars, Stars};
+
+    #[test]
+    fn marks_stars() {
+        let mut stars = Stars::default();
+        assert_eq!(stars.mark(day!(2), 1), Ok(true));
+        assert_eq!(stars.mark(day!(1), 2), Ok(true));
+        assert_eq!(stars.mark(day!(2), 1), Ok(false));
+        assert_eq!(stars.total(), 2);
+        assert_eq!(stars.data[0].day, day!(1));
+        assert_eq!(stars.data[1].day, day!(2));
+    }
+
+    #[test]
+    fn handles_complete_days() {
+        let mut stars = Stars::default();
+        stars.mark(day!(1), 1).unwrap();
+        stars.mark(day!(25), 1).unwrap();
+        assert_eq!(stars.is_day_complete(day!(1)), false);
+        assert_eq!(stars.is_day_complete(day!(25)), true);
+        stars.mark(day!(1), 2).unwrap();
+        assert_eq!(stars.is_day_complete(day!(1)), true);
+    }
+
+    #[test]
+    fn rejects_missing_parts() {
+        let mut stars = Stars::default();
+        assert!(stars.mark(day!(25), 2).is_err());
+        assert!(stars.mark(day!(1), 0).is_err());
+        assert!(stars.mark(day!(1), 3).is_err());
+        assert_eq!(stars.total(), 0);
+    }
+
+    #[test]
+    fn renders_extra_stars_of_single_part_days() {
+        let stars = Stars {
+            data: vec![DayStars {
+                day: day!(25),
+                part_1: true,
+                part_2: true,
+            }],
+        };
+        assert_eq!(stars.calendar().ends_with("25 ⭐⭐ "), true);
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let mut stars = Stars::default();
+        stars.mark(day!(3), 1).unwrap();
+        let json = tinyjson::JsonValue::from(stars).stringify().unwrap();
+        let stars = Stars::try_from(json).unwrap();
+        assert_eq!(stars.data.len(), 1);
+        assert_eq!(stars.data[0].part_1, true);
+        assert_eq!(stars.data[0].part_2, false);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_stars() {
+        let json = r#"{ "data": [{ "day": "01", "part_1": "yes" }] }"#.to_string();
+        Stars::try_from(json).unwrap();
+    }
+
+    #[test]
+    fn renders_calendar() {
+        let mut stars = Stars::default();
+        stars.mark(day!(1), 1).unwrap();
+        stars.mark(day!(1), 2).unwrap();
+        stars.mark(day!(2), 1).unwrap();
+        let calendar = stars.calendar();
+        assert_eq!(calendar.lines().count(), 5);
+        assert_eq!(
+            calendar
+                .lines()
+                .next()
+                .unwrap()
+                .starts_with("01 ⭐⭐   02 ⭐☆"),
+            true
+        );
+        assert_eq!(calendar.ends_with("25 ☆ "), true);
+    }
+}
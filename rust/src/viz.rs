@@ -0,0 +1,491 @@
+//! Rendering of maps and simulations, to the terminal or to image files.
+//!
+//! Draw the state into a [`Canvas`], e.g. with [`Canvas::from_grid`], mark paths on it with
+//! [`Canvas::overlay`] or [`Canvas::highlight`], then print it with [`Canvas::to_ansi`] or save
+//! it with [`Canvas::save`]. An [`Animation`] plays a sequence of canvases in the terminal and
+//! exports them as numbered PPM or PNG frames or as an animated GIF.
+//!
+//! Files are written to `target/viz/`. Solutions only draw when [`enabled`] returns `true`, i.e.
+//! when the environment variable `AOC_VIZ` is set, so that visualizing costs nothing otherwise.
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+use ndarray::Array2;
+
+use crate::{geometry::Location, grid::Grid};
+
+/// Whether solutions should visualize their state, which is the case if `AOC_VIZ` is set.
+pub fn enabled() -> bool {
+    env::var_os("AOC_VIZ").is_some()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+    pub const GRAY: Self = Self::new(128, 128, 128);
+    pub const RED: Self = Self::new(220, 50, 47);
+    pub const GREEN: Self = Self::new(64, 200, 64);
+    pub const BLUE: Self = Self::new(38, 139, 210);
+    pub const YELLOW: Self = Self::new(240, 200, 0);
+    pub const ORANGE: Self = Self::new(240, 130, 20);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+/// A single tile of a canvas: the glyph shown in the terminal and an optional color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    pub const EMPTY: Self = Self::new(' ');
+
+    pub const fn new(glyph: char) -> Self {
+        Self { glyph, color: None }
+    }
+
+    pub const fn colored(glyph: char, color: Color) -> Self {
+        Self {
+            glyph,
+            color: Some(color),
+        }
+    }
+
+    /// The color of the cell in an image. Uncolored cells are black if they look empty and white
+    /// otherwise.
+    pub fn pixel(&self) -> Color {
+        match self.color {
+            Some(color) => color,
+            None if self.glyph.is_whitespace() || self.glyph == '.' => Color::BLACK,
+            None => Color::WHITE,
+        }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(glyph: char) -> Self {
+        Self::new(glyph)
+    }
+}
+
+/// The image formats a canvas or an animation can be saved in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+            Self::Gif => "gif",
+        }
+    }
+}
+
+/// A map of cells to render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    cells: Grid<Cell>,
+}
+
+impl Canvas {
+    /// An empty canvas.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::EMPTY),
+        }
+    }
+
+    /// Draws every tile of `grid` with the cell returned by `draw`.
+    pub fn from_grid<T>(grid: &Grid<T>, draw: impl FnMut(&T) -> Cell) -> Self {
+        Self {
+            cells: grid.map(draw),
+        }
+    }
+
+    /// Draws every tile of `array` with the cell returned by `draw`.
+    pub fn from_array<T>(array: &Array2<T>, draw: impl FnMut(&T) -> Cell) -> Self {
+        let (height, width) = array.dim();
+        Self {
+            cells: Grid::from_vec(width, height, array.iter().map(draw).collect()),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn get(&self, at: impl Location) -> Option<Cell> {
+        let index = at.locate(self.cells.dim())?;
+        Some(self.cells[index])
+    }
+
+    /// Draws `cell` at `at`. Locations outside of the canvas are ignored.
+    pub fn set(&mut self, at: impl Location, cell: Cell) {
+        if let Some(index) = at.locate(self.cells.dim()) {
+            self.cells[index] = cell;
+        }
+    }
+
+    /// Draws `cell` at every location of `path`.
+    pub fn overlay<L: Location>(&mut self, path: impl IntoIterator<Item = L>, cell: Cell) {
+        for at in path {
+            self.set(at, cell);
+        }
+    }
+
+    /// Colors every location of `path`, keeping the glyphs.
+    pub fn highlight<L: Location>(&mut self, path: impl IntoIterator<Item = L>, color: Color) {
+        for at in path {
+            if let Some(index) = at.locate(self.cells.dim()) {
+                self.cells[index].color = Some(color);
+            }
+        }
+    }
+
+    /// Renders the canvas with ANSI true color escape codes.
+    pub fn to_ansi(&self) -> String {
+        let mut output = String::new();
+        for (index, row) in self.cells.rows().enumerate() {
+            if index > 0 {
+                output.push('\n');
+            }
+            let mut current = None;
+            for cell in row {
+                if cell.color != current {
+                    match cell.color {
+                        Some(Color { r, g, b }) => output += &format!("\x1b[38;2;{r};{g};{b}m"),
+                        None => output += "\x1b[0m",
+                    }
+                    current = cell.color;
+                }
+                output.push(cell.glyph);
+            }
+            if current.is_some() {
+                output += "\x1b[0m";
+            }
+        }
+        output
+    }
+
+    /// The pixels of the canvas as RGB bytes, with every cell drawn as a `scale` by `scale`
+    /// square. Returns the width and height of the image and its pixels.
+    pub fn to_rgb(&self, scale: usize) -> (usize, usize, Vec<u8>) {
+        let scale = scale.max(1);
+        let (width, height) = (self.width() * scale, self.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for row in self.cells.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|cell| {
+                    let Color { r, g, b } = cell.pixel();
+                    [r, g, b].repeat(scale)
+                })
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        (width, height, pixels)
+    }
+
+    /// Saves the canvas as `target/viz/<name>.<format>` and returns the path.
+    pub fn save(&self, name: &str, format: Format, scale: usize) -> io::Result<PathBuf> {
+        let path = output_dir()?.join(format!("{name}.{}", format.extension()));
+        match format {
+            Format::Ppm => write_ppm(&path, self, scale)?,
+            Format::Png => write_png(&path, self, scale)?,
+            Format::Gif => write_gif(&path, [self], scale, 0)?,
+        }
+        Ok(path)
+    }
+}
+
+/// Prints the glyphs without colors.
+impl Display for Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cells.map(|cell| cell.glyph))
+    }
+}
+
+/// A sequence of canvases that is played in the terminal, recorded for export, or both.
+#[derive(Debug)]
+pub struct Animation {
+    name: String,
+    frame_time: Duration,
+    every: usize,
+    play: bool,
+    record: bool,
+    frames: Vec<Canvas>,
+    pushed: usize,
+    last_shown: Option<Instant>,
+}
+
+impl Animation {
+    /// An animation that plays in the terminal at `fps` frames per second.
+    pub fn play(name: &str, fps: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            frame_time: Duration::from_secs(1) / fps.max(1),
+            every: 1,
+            play: true,
+            record: false,
+            frames: Vec::new(),
+            pushed: 0,
+            last_shown: None,
+        }
+    }
+
+    /// An animation that is only recorded for export, at `fps` frames per second.
+    pub fn record(name: &str, fps: u32) -> Self {
+        Self {
+            play: false,
+            record: true,
+            ..Self::play(name, fps)
+        }
+    }
+
+    /// Also records the frames of a played animation.
+    pub fn recorded(mut self) -> Self {
+        self.record = true;
+        self
+    }
+
+    /// Only keeps every `n`th frame, e.g. for simulations with thousands of steps.
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// The recorded frames.
+    pub fn frames(&self) -> &[Canvas] {
+        &self.frames
+    }
+
+    /// Adds a frame. Playing animations wait for the frame time to pass before they draw it.
+    pub fn frame(&mut self, canvas: Canvas) {
+        let is_skipped = !self.pushed.is_multiple_of(self.every);
+        self.pushed += 1;
+        if is_skipped {
+            return;
+        }
+
+        if self.play {
+            if let Some(elapsed) = self.last_shown.map(|last| last.elapsed()) {
+                thread::sleep(self.frame_time.saturating_sub(elapsed));
+            }
+            // move the cursor home and clear the screen before every frame.
+            println!("\x1b[H\x1b[2J{}", canvas.to_ansi());
+            let _ = io::stdout().flush();
+            self.last_shown = Some(Instant::now());
+        }
+        if self.record {
+            self.frames.push(canvas);
+        }
+    }
+
+    /// Saves the recorded frames. PPM and PNG frames are numbered files in the directory
+    /// `target/viz/<name>/`, a GIF is saved as `target/viz/<name>.gif`.
+    /// Returns the path of the directory or the GIF.
+    pub fn export(&self, format: Format, scale: usize) -> io::Result<PathBuf> {
+        if format == Format::Gif {
+            let path = output_dir()?.join(format!("{}.gif", self.name));
+            let delay = (self.frame_time.as_millis() / 10).min(u16::MAX as u128) as u16;
+            write_gif(&path, &self.frames, scale, delay)?;
+            return Ok(path);
+        }
+
+        let directory = output_dir()?.join(&self.name);
+        fs::create_dir_all(&directory)?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            let path = directory.join(format!("{index:05}.{}", format.extension()));
+            match format {
+                Format::Ppm => write_ppm(&path, frame, scale)?,
+                _ => write_png(&path, frame, scale)?,
+            }
+        }
+        Ok(directory)
+    }
+}
+
+fn output_dir() -> io::Result<PathBuf> {
+    let directory = env::current_dir()?.join("target").join("viz");
+    fs::create_dir_all(&directory)?;
+    Ok(directory)
+}
+
+fn write_ppm(path: &PathBuf, canvas: &Canvas, scale: usize) -> io::Result<()> {
+    let (width, height, pixels) = canvas.to_rgb(scale);
+    let mut file = BufWriter::new(File::create(path)?);
+    write!(file, "P6\n{width} {height}\n255\n")?;
+    file.write_all(&pixels)?;
+    file.flush()
+}
+
+fn write_png(path: &PathBuf, canvas: &Canvas, scale: usize) -> io::Result<()> {
+    let (width, height, pixels) = canvas.to_rgb(scale);
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        width as u32,
+        height as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&pixels).map_err(io::Error::other)
+}
+
+/// Writes `frames` as a looping GIF with `delay` hundredths of a second between frames.
+fn write_gif<'a>(
+    path: &PathBuf,
+    frames: impl IntoIterator<Item = &'a Canvas>,
+    scale: usize,
+    delay: u16,
+) -> io::Result<()> {
+    let frames: Vec<_> = frames
+        .into_iter()
+        .map(|frame| frame.to_rgb(scale))
+        .collect();
+    let Some(&(width, height, _)) = frames.first() else {
+        return Err(io::Error::other("the animation has no frames"));
+    };
+    let too_large = |_| io::Error::other("the image is too large for a GIF");
+    let (width, height) = (
+        u16::try_from(width).map_err(too_large)?,
+        u16::try_from(height).map_err(too_large)?,
+    );
+
+    // maps usually have few colors, so a shared palette avoids quantizing every frame.
+    let palette = shared_palette(frames.iter().map(|(_, _, pixels)| pixels.as_slice()));
+    let palette = palette.unwrap_or_default();
+
+    let mut colors = vec![0; palette.len() * 3];
+    for (color, &index) in &palette {
+        colors[index as usize * 3..][..3].copy_from_slice(color);
+    }
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &colors).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+
+    for (_, _, pixels) in frames {
+        let mut frame = if palette.is_empty() {
+            gif::Frame::from_rgb_speed(width, height, &pixels, 10)
+        } else {
+            let indices: Vec<u8> = pixels.chunks_exact(3).map(|pixel| palette[pixel]).collect();
+            gif::Frame::from_indexed_pixels(width, height, indices, None)
+        };
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+/// Assigns an index to every color of `images`, unless there are more than 256 of them.
+fn shared_palette<'a>(images: impl Iterator<Item = &'a [u8]>) -> Option<HashMap<[u8; 3], u8>> {
+    let mut palette = HashMap::new();
+    for pixels in images {
+        for pixel in pixels.chunks_exact(3) {
+            let next = palette.len();
+            if !palette.contains_key(pixel) {
+                palette.insert([pixel[0], pixel[1], pixel[2]], u8::try_from(next).ok()?);
+            }
+        }
+    }
+    Some(palette)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::{Animation, Canvas, Cell, Color};
+    use crate::{geometry::Point, grid::Grid};
+
+    fn maze() -> Canvas {
+        let grid = Grid::parse("#.#\n..#", |c| c == '#');
+        Canvas::from_grid(&grid, |&wall| if wall { '#'.into() } else { '.'.into() })
+    }
+
+    #[test]
+    fn draws_grids() {
+        let canvas = maze();
+        assert_eq!(canvas.to_string(), "#.#\n..#");
+
+        let canvas = Canvas::from_array(&array![[1, 2], [3, 4]], |&n| {
+            Cell::new(char::from_digit(n, 10).unwrap())
+        });
+        assert_eq!(canvas.to_string(), "12\n34");
+    }
+
+    #[test]
+    fn overlays_paths() {
+        let mut canvas = maze();
+        canvas.overlay([(0, 1), (1, 1)], Cell::new('O'));
+        canvas.overlay([Point::new(0, 1), Point::new(-1, 0)], Cell::new('S'));
+        assert_eq!(canvas.to_string(), "#O#\nSO#");
+
+        canvas.highlight([(0, 0)], Color::RED);
+        assert_eq!(canvas.get((0, 0)), Some(Cell::colored('#', Color::RED)));
+        assert_eq!(canvas.get(Point::new(5, 5)), None);
+    }
+
+    #[test]
+    fn renders_ansi_colors() {
+        let mut canvas = Canvas::new(3, 1);
+        canvas.overlay([(0, 0), (0, 1)], Cell::colored('x', Color::RED));
+        assert_eq!(canvas.to_ansi(), "\x1b[38;2;220;50;47mxx\x1b[0m ");
+    }
+
+    #[test]
+    fn scales_pixels() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set((0, 1), Cell::new('#'));
+
+        let (width, height, pixels) = canvas.to_rgb(2);
+        assert_eq!((width, height), (4, 2));
+        let row = [0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255];
+        assert_eq!(pixels, [row, row].concat());
+    }
+
+    #[test]
+    fn samples_frames() {
+        let mut animation = Animation::record("test", 10).every(3);
+        for _ in 0..7 {
+            animation.frame(maze());
+        }
+        assert_eq!(animation.frames().len(), 3);
+    }
+}
@@ -0,0 +1,404 @@
+//! The 3-bit computer of the chronospatial puzzles.
+//!
+//! A program is a list of 3-bit numbers, read as pairs of an opcode and an operand. A
+//! [`Machine`] runs it on three `u64` registers, optionally with a step limit and a trace of
+//! every executed instruction. [`disassemble`] prints a program as mnemonics, and
+//! [`find_quine`] searches the register A that makes a program output itself.
+use std::fmt::Display;
+
+use crate::error::AocError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    /// `A = A >> combo`
+    Adv,
+    /// `B = B ^ literal`
+    Bxl,
+    /// `B = combo % 8`
+    Bst,
+    /// Jumps to `literal` unless `A` is 0.
+    Jnz,
+    /// `B = B ^ C`, ignoring the operand.
+    Bxc,
+    /// Outputs `combo % 8`.
+    Out,
+    /// `B = A >> combo`
+    Bdv,
+    /// `C = A >> combo`
+    Cdv,
+}
+
+impl Opcode {
+    pub const ALL: [Self; 8] = [
+        Self::Adv,
+        Self::Bxl,
+        Self::Bst,
+        Self::Jnz,
+        Self::Bxc,
+        Self::Out,
+        Self::Bdv,
+        Self::Cdv,
+    ];
+
+    /// The opcode of a 3-bit number; only the lowest three bits are used.
+    pub fn from_bits(bits: u8) -> Self {
+        Self::ALL[(bits & 0b111) as usize]
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Self::Adv => "adv",
+            Self::Bxl => "bxl",
+            Self::Bst => "bst",
+            Self::Jnz => "jnz",
+            Self::Bxc => "bxc",
+            Self::Out => "out",
+            Self::Bdv => "bdv",
+            Self::Cdv => "cdv",
+        }
+    }
+
+    /// Whether the operand is a combo operand, which can refer to a register.
+    pub fn takes_combo(&self) -> bool {
+        matches!(
+            self,
+            Self::Adv | Self::Bst | Self::Out | Self::Bdv | Self::Cdv
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: u8,
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mnemonic = self.opcode.mnemonic();
+        match (self.opcode, self.operand) {
+            (Opcode::Bxc, _) => write!(f, "{mnemonic}"),
+            (opcode, 4) if opcode.takes_combo() => write!(f, "{mnemonic} A"),
+            (opcode, 5) if opcode.takes_combo() => write!(f, "{mnemonic} B"),
+            (opcode, 6) if opcode.takes_combo() => write!(f, "{mnemonic} C"),
+            (opcode, 7) if opcode.takes_combo() => write!(f, "{mnemonic} <reserved>"),
+            (_, operand) => write!(f, "{mnemonic} {operand}"),
+        }
+    }
+}
+
+/// The instructions of `program` with their positions, one per line, e.g. `0: bst A`.
+/// A trailing opcode without an operand is left out, since it is never executed.
+pub fn disassemble(program: &[u8]) -> String {
+    program
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let instruction = Instruction {
+                opcode: Opcode::from_bits(pair[0]),
+                operand: pair[1],
+            };
+            format!("{:>2}: {instruction}\n", index * 2)
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Display for Registers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "A={} B={} C={}", self.a, self.b, self.c)
+    }
+}
+
+/// An executed instruction, with the registers before it ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub pointer: usize,
+    pub instruction: Instruction,
+    pub registers: Registers,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let instruction = self.instruction.to_string();
+        write!(
+            f,
+            "{:>2}: {instruction:<12}{}",
+            self.pointer, self.registers
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmError {
+    /// The combo operand 7 was used by the instruction at `pointer`.
+    ReservedOperand { pointer: usize },
+    /// The program did not halt within the given number of steps.
+    StepLimit(usize),
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReservedOperand { pointer } => {
+                write!(
+                    f,
+                    "the instruction at {pointer} uses the reserved operand 7"
+                )
+            }
+            Self::StepLimit(limit) => write!(f, "the program did not halt within {limit} steps"),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+impl From<VmError> for AocError {
+    fn from(error: VmError) -> Self {
+        AocError::new(error.to_string())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub registers: Registers,
+    pub pointer: usize,
+    output: Vec<u8>,
+    steps: usize,
+    step_limit: Option<usize>,
+    trace: Option<Vec<Step>>,
+}
+
+impl Machine {
+    pub fn new(registers: Registers) -> Self {
+        Self {
+            registers,
+            ..Default::default()
+        }
+    }
+
+    /// Stops the program with [`VmError::StepLimit`] after `limit` instructions.
+    pub fn with_step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    /// Records every executed instruction, see [`Machine::trace`].
+    pub fn traced(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// The number of instructions executed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The executed instructions, if the machine is [`Machine::traced`].
+    pub fn trace(&self) -> &[Step] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// The instruction at the instruction pointer, or `None` if the program has halted.
+    pub fn current(&self, program: &[u8]) -> Option<Instruction> {
+        Some(Instruction {
+            opcode: Opcode::from_bits(*program.get(self.pointer)?),
+            operand: *program.get(self.pointer + 1)?,
+        })
+    }
+
+    /// Runs `program` until it halts and returns its output.
+    pub fn run(&mut self, program: &[u8]) -> Result<&[u8], VmError> {
+        while self.step(program)?.is_some() {}
+        Ok(&self.output)
+    }
+
+    /// Executes a single instruction and returns it, or `None` if the program has halted.
+    pub fn step(&mut self, program: &[u8]) -> Result<Option<Instruction>, VmError> {
+        let Some(instruction) = self.current(program) else {
+            return Ok(None);
+        };
+        if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+            return Err(VmError::StepLimit(self.steps));
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(Step {
+                pointer: self.pointer,
+                instruction,
+                registers: self.registers,
+            });
+        }
+        self.steps += 1;
+
+        let Instruction { opcode, operand } = instruction;
+        let literal = operand as u64;
+        let registers = &mut self.registers;
+        // an error only if an instruction that takes a combo operand uses it.
+        let combo = match operand {
+            0..=3 => Ok(literal),
+            4 => Ok(registers.a),
+            5 => Ok(registers.b),
+            6 => Ok(registers.c),
+            _ => Err(VmError::ReservedOperand {
+                pointer: self.pointer,
+            }),
+        };
+
+        match opcode {
+            Opcode::Adv => registers.a = shift(registers.a, combo?),
+            Opcode::Bxl => registers.b ^= literal,
+            Opcode::Bst => registers.b = combo? % 8,
+            Opcode::Jnz if registers.a != 0 => {
+                self.pointer = operand as usize;
+                return Ok(Some(instruction));
+            }
+            Opcode::Jnz => {}
+            Opcode::Bxc => registers.b ^= registers.c,
+            Opcode::Out => self.output.push((combo? % 8) as u8),
+            Opcode::Bdv => registers.b = shift(registers.a, combo?),
+            Opcode::Cdv => registers.c = shift(registers.a, combo?),
+        }
+        self.pointer += 2;
+        Ok(Some(instruction))
+    }
+}
+
+/// `value / 2^by`, which is 0 for shifts of 64 bits and more.
+fn shift(value: u64, by: u64) -> u64 {
+    u32::try_from(by)
+        .ok()
+        .and_then(|by| value.checked_shr(by))
+        .unwrap_or(0)
+}
+
+/// The smallest register A for which `program` outputs itself, with B and C starting at 0.
+///
+/// Assumes the structure of the puzzle programs: a single loop that outputs one value per
+/// iteration, shifts A right by 3 and jumps back while A is not 0, so that each output only
+/// depends on the octal digits of A from its position upwards. A is built from its most
+/// significant octal digit down, backtracking when no digit reproduces the rest of the program.
+/// Every candidate is run on the machine, so a returned A always makes the program output
+/// itself; for programs of another shape it may just not be the smallest one.
+pub fn find_quine(program: &[u8]) -> Option<u64> {
+    find_quine_digits(program, program.len(), 0)
+}
+
+/// Extends `prefix` by one octal digit so that the program outputs `program[index - 1..]`.
+fn find_quine_digits(program: &[u8], index: usize, prefix: u64) -> Option<u64> {
+    let Some(index) = index.checked_sub(1) else {
+        return Some(prefix);
+    };
+    // an A of more than 21 octal digits does not fit into a u64.
+    if prefix >> 61 != 0 {
+        return None;
+    }
+
+    (0..8).find_map(|digit| {
+        let a = prefix << 3 | digit;
+        let mut machine = Machine::new(Registers { a, b: 0, c: 0 })
+            .with_step_limit(QUINE_STEP_LIMIT * program.len());
+        match machine.run(program) {
+            Ok(output) if output == &program[index..] => find_quine_digits(program, index, a),
+            _ => None,
+        }
+    })
+}
+
+/// The number of steps per output value after which a candidate is given up on.
+const QUINE_STEP_LIMIT: usize = 1_000;
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{disassemble, find_quine, Machine, Registers, VmError};
+
+    fn run(a: u64, b: u64, c: u64, program: &[u8]) -> Machine {
+        let mut machine = Machine::new(Registers { a, b, c });
+        machine.run(program).unwrap();
+        machine
+    }
+
+    #[test]
+    fn executes_instructions() {
+        assert_eq!(run(0, 0, 9, &[2, 6]).registers.b, 1);
+        assert_eq!(run(10, 0, 0, &[5, 0, 5, 1, 5, 4]).output(), [0, 1, 2]);
+        assert_eq!(run(0, 29, 0, &[1, 7]).registers.b, 26);
+        assert_eq!(run(0, 2024, 43690, &[4, 0]).registers.b, 44354);
+
+        let machine = run(2024, 0, 0, &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(machine.output(), [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(machine.registers.a, 0);
+    }
+
+    #[test]
+    fn handles_large_registers() {
+        let a = 1 << 62;
+        assert_eq!(run(a, 0, 0, &[6, 4]).registers.b, 0);
+        assert_eq!(run(a, 2, 0, &[7, 5]).registers.c, 1 << 60);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut machine = Machine::new(Registers::default());
+        assert_eq!(
+            machine.run(&[1, 3, 5, 7]),
+            Err(VmError::ReservedOperand { pointer: 2 })
+        );
+
+        let mut machine = Machine::new(Registers { a: 1, b: 0, c: 0 }).with_step_limit(10);
+        assert_eq!(machine.run(&[3, 0]), Err(VmError::StepLimit(10)));
+        assert_eq!(machine.steps(), 10);
+    }
+
+    #[test]
+    fn disassembles_programs() {
+        let expected = " 0: adv 3\n 2: out A\n 4: bxc\n 6: jnz 0\n";
+        assert_eq!(disassemble(&[0, 3, 5, 4, 4, 1, 3, 0, 7]), expected);
+    }
+
+    #[test]
+    fn traces_instructions() {
+        let mut machine = Machine::new(Registers { a: 16, b: 0, c: 0 }).traced();
+        machine.run(&[0, 3, 5, 4, 3, 0]).unwrap();
+
+        let trace: Vec<_> = machine.trace().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            trace,
+            [
+                " 0: adv 3       A=16 B=0 C=0",
+                " 2: out A       A=2 B=0 C=0",
+                " 4: jnz 0       A=2 B=0 C=0",
+                " 0: adv 3       A=2 B=0 C=0",
+                " 2: out A       A=0 B=0 C=0",
+                " 4: jnz 0       A=0 B=0 C=0",
+            ]
+        );
+        assert!(Machine::default().trace().is_empty());
+    }
+
+    #[test]
+    fn finds_quines() {
+        let program = [0, 3, 5, 4, 3, 0];
+        assert_eq!(find_quine(&program), Some(117440));
+
+        let brute_force = (0..).find(|&a| run(a, 0, 0, &program).output() == program);
+        assert_eq!(brute_force, Some(117440));
+    }
+
+    #[test]
+    fn rejects_programs_without_quines() {
+        // outputs A % 8 forever unless A is 0, in which case it only outputs 0.
+        assert_eq!(find_quine(&[5, 4, 3, 0]), None);
+    }
+}